@@ -1,5 +1,9 @@
+#[cfg(feature = "async")]
+use futures::{future, Future};
 use query::Query;
 use {Album, Artist, Client, Error, Result, Song};
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture};
 
 /// Allows starring, rating, and scrobbling media.
 pub trait Annotatable {
@@ -31,109 +35,275 @@ pub trait Annotatable {
     where
         B: Into<Option<bool>>,
         T: Into<Option<&'a str>>;
+
+    /// Attaches a star to the content without blocking.
+    #[cfg(feature = "async")]
+    fn star_async(&self, client: &AsyncClient) -> SunkFuture<()>;
+
+    /// Removes a star from the content without blocking.
+    #[cfg(feature = "async")]
+    fn unstar_async(&self, client: &AsyncClient) -> SunkFuture<()>;
+
+    /// Sets the rating for the content without blocking.
+    #[cfg(feature = "async")]
+    fn set_rating_async(&self, client: &AsyncClient, rating: u8) -> SunkFuture<()>;
+
+    /// Registers the local playback of the content without blocking.
+    ///
+    /// See [`scrobble`](#tymethod.scrobble) for details.
+    #[cfg(feature = "async")]
+    fn scrobble_async<'a, B, T>(
+        &self,
+        client: &AsyncClient,
+        time: T,
+        now_playing: B,
+    ) -> SunkFuture<()>
+    where
+        B: Into<Option<bool>>,
+        T: Into<Option<&'a str>>;
 }
 
 impl Annotatable for Artist {
     fn star(&self, client: &Client) -> Result<()> {
-        client.get("star", Query::with("artistId", self.id))?;
+        client.get("star", Query::with("artistId", &self.id))?;
         Ok(())
     }
 
+    #[cfg(feature = "async")]
+    fn star_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get("star", Query::with("artistId", &self.id))
+                .map(|_| ()),
+        )
+    }
+
     fn unstar(&self, client: &Client) -> Result<()> {
-        client.get("unstar", Query::with("artistId", self.id))?;
+        client.get("unstar", Query::with("artistId", &self.id))?;
         Ok(())
     }
 
+    #[cfg(feature = "async")]
+    fn unstar_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get("unstar", Query::with("artistId", &self.id))
+                .map(|_| ()),
+        )
+    }
+
     fn set_rating(&self, client: &Client, rating: u8) -> Result<()> {
         if rating > 5 {
             return Err(Error::Other("rating must be between 0 and 5 inclusive"));
         }
 
-        let args = Query::with("id", self.id).arg("rating", rating).build();
+        let args = Query::with("id", &self.id).arg("rating", rating).build();
         client.get("setRating", args)?;
         Ok(())
     }
 
+    #[cfg(feature = "async")]
+    fn set_rating_async(&self, client: &AsyncClient, rating: u8) -> SunkFuture<()> {
+        if rating > 5 {
+            return Box::new(future::err(Error::Other(
+                "rating must be between 0 and 5 inclusive",
+            )));
+        }
+
+        let args = Query::with("id", &self.id).arg("rating", rating).build();
+        Box::new(client.get("setRating", args).map(|_| ()))
+    }
+
     fn scrobble<'a, B, T>(&self, client: &Client, time: T, now_playing: B) -> Result<()>
     where
         B: Into<Option<bool>>,
         T: Into<Option<&'a str>>,
     {
-        let args = Query::with("id", self.id)
+        let args = Query::with("id", &self.id)
             .arg("time", time.into())
             .arg("submission", now_playing.into().map(|b| !b))
             .build();
         client.get("scrobble", args)?;
         Ok(())
     }
+
+    #[cfg(feature = "async")]
+    fn scrobble_async<'a, B, T>(
+        &self,
+        client: &AsyncClient,
+        time: T,
+        now_playing: B,
+    ) -> SunkFuture<()>
+    where
+        B: Into<Option<bool>>,
+        T: Into<Option<&'a str>>,
+    {
+        let args = Query::with("id", &self.id)
+            .arg("time", time.into())
+            .arg("submission", now_playing.into().map(|b| !b))
+            .build();
+        Box::new(client.get("scrobble", args).map(|_| ()))
+    }
 }
 
 impl Annotatable for Album {
     fn star(&self, client: &Client) -> Result<()> {
-        client.get("star", Query::with("albumId", self.id))?;
+        client.get("star", Query::with("albumId", &self.id))?;
         Ok(())
     }
 
+    #[cfg(feature = "async")]
+    fn star_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get("star", Query::with("albumId", &self.id))
+                .map(|_| ()),
+        )
+    }
+
     fn unstar(&self, client: &Client) -> Result<()> {
-        client.get("unstar", Query::with("albumId", self.id))?;
+        client.get("unstar", Query::with("albumId", &self.id))?;
         Ok(())
     }
 
+    #[cfg(feature = "async")]
+    fn unstar_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get("unstar", Query::with("albumId", &self.id))
+                .map(|_| ()),
+        )
+    }
+
     fn set_rating(&self, client: &Client, rating: u8) -> Result<()> {
         if rating > 5 {
             return Err(Error::Other("rating must be between 0 and 5 inclusive"));
         }
 
-        let args = Query::with("id", self.id).arg("rating", rating).build();
+        let args = Query::with("id", &self.id).arg("rating", rating).build();
         client.get("setRating", args)?;
         Ok(())
     }
 
+    #[cfg(feature = "async")]
+    fn set_rating_async(&self, client: &AsyncClient, rating: u8) -> SunkFuture<()> {
+        if rating > 5 {
+            return Box::new(future::err(Error::Other(
+                "rating must be between 0 and 5 inclusive",
+            )));
+        }
+
+        let args = Query::with("id", &self.id).arg("rating", rating).build();
+        Box::new(client.get("setRating", args).map(|_| ()))
+    }
+
     fn scrobble<'a, B, T>(&self, client: &Client, time: T, now_playing: B) -> Result<()>
     where
         B: Into<Option<bool>>,
         T: Into<Option<&'a str>>,
     {
-        let args = Query::with("id", self.id)
+        let args = Query::with("id", &self.id)
             .arg("time", time.into())
             .arg("submission", now_playing.into().map(|b| !b))
             .build();
         client.get("scrobble", args)?;
         Ok(())
     }
+
+    #[cfg(feature = "async")]
+    fn scrobble_async<'a, B, T>(
+        &self,
+        client: &AsyncClient,
+        time: T,
+        now_playing: B,
+    ) -> SunkFuture<()>
+    where
+        B: Into<Option<bool>>,
+        T: Into<Option<&'a str>>,
+    {
+        let args = Query::with("id", &self.id)
+            .arg("time", time.into())
+            .arg("submission", now_playing.into().map(|b| !b))
+            .build();
+        Box::new(client.get("scrobble", args).map(|_| ()))
+    }
 }
 
 impl Annotatable for Song {
     fn star(&self, client: &Client) -> Result<()> {
-        client.get("star", Query::with("id", self.id))?;
+        client.get("star", Query::with("id", &self.id))?;
         Ok(())
     }
 
+    #[cfg(feature = "async")]
+    fn star_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(client.get("star", Query::with("id", &self.id)).map(|_| ()))
+    }
+
     fn unstar(&self, client: &Client) -> Result<()> {
-        client.get("unstar", Query::with("id", self.id))?;
+        client.get("unstar", Query::with("id", &self.id))?;
         Ok(())
     }
 
+    #[cfg(feature = "async")]
+    fn unstar_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get("unstar", Query::with("id", &self.id))
+                .map(|_| ()),
+        )
+    }
+
     fn set_rating(&self, client: &Client, rating: u8) -> Result<()> {
         if rating > 5 {
             return Err(Error::Other("rating must be between 0 and 5 inclusive"));
         }
 
-        let args = Query::with("id", self.id).arg("rating", rating).build();
+        let args = Query::with("id", &self.id).arg("rating", rating).build();
         client.get("setRating", args)?;
         Ok(())
     }
 
+    #[cfg(feature = "async")]
+    fn set_rating_async(&self, client: &AsyncClient, rating: u8) -> SunkFuture<()> {
+        if rating > 5 {
+            return Box::new(future::err(Error::Other(
+                "rating must be between 0 and 5 inclusive",
+            )));
+        }
+
+        let args = Query::with("id", &self.id).arg("rating", rating).build();
+        Box::new(client.get("setRating", args).map(|_| ()))
+    }
+
     fn scrobble<'a, B, T>(&self, client: &Client, time: T, now_playing: B) -> Result<()>
     where
         B: Into<Option<bool>>,
         T: Into<Option<&'a str>>,
     {
-        let args = Query::with("id", self.id)
+        let args = Query::with("id", &self.id)
             .arg("time", time.into())
             .arg("submission", now_playing.into().map(|b| !b))
             .build();
         client.get("scrobble", args)?;
         Ok(())
     }
+
+    #[cfg(feature = "async")]
+    fn scrobble_async<'a, B, T>(
+        &self,
+        client: &AsyncClient,
+        time: T,
+        now_playing: B,
+    ) -> SunkFuture<()>
+    where
+        B: Into<Option<bool>>,
+        T: Into<Option<&'a str>>,
+    {
+        let args = Query::with("id", &self.id)
+            .arg("time", time.into())
+            .arg("submission", now_playing.into().map(|b| !b))
+            .build();
+        Box::new(client.get("scrobble", args).map(|_| ()))
+    }
 }
@@ -0,0 +1,506 @@
+use futures::future::{self, Either};
+use futures::{Future, Stream};
+use reqwest::async::{Client as ReqwestClient, Response as ReqwestResponse};
+use reqwest::Url;
+use serde_json;
+use std::cmp;
+
+use auth::Auth;
+use capabilities::{Capabilities, Extension, ServerInfo, ServerState};
+use client::{self, License};
+use id::{MusicFolderId, SongId};
+use media::NowPlaying;
+use play_queue::{self, PlayQueue};
+use query::Query;
+use response::{self, Response};
+use search::{SearchPage, SearchResult};
+use {ApiError, Error, Genre, Hls, Lyrics, MusicFolder, Result, Song, Version};
+
+/// A boxed future resolving to a `sunk` result.
+///
+/// Every asynchronous method in the crate returns one of these. The futures are
+/// built on `futures` 0.1, and must be driven by a `tokio` 0.1 runtime.
+pub type SunkFuture<T> = Box<dyn Future<Item = T, Error = Error> + Send>;
+
+/// A boxed stream of `sunk` results.
+///
+/// Used where the asynchronous API yields data piecemeal, such as the chunks
+/// of a media download.
+pub type SunkStream<T> = Box<dyn Stream<Item = T, Error = Error> + Send>;
+
+/// A non-blocking client to make requests to a Subsonic instance.
+///
+/// The `AsyncClient` is the asynchronous counterpart to [`Client`], and is
+/// only available with the `async` feature enabled. It shares its URL
+/// construction, authentication, and response handling with the blocking
+/// client; the only difference is that requests return a [`SunkFuture`]
+/// instead of blocking the current thread.
+///
+/// Methods that issue requests through an `AsyncClient` are suffixed with
+/// `_async`, and otherwise mirror their blocking counterparts.
+///
+/// [`Client`]: ./struct.Client.html
+/// [`SunkFuture`]: ./type.SunkFuture.html
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// extern crate futures;
+/// extern crate sunk;
+///
+/// use futures::Future;
+/// use sunk::song::Song;
+/// use sunk::AsyncClient;
+///
+/// # fn run() -> sunk::Result<()> {
+/// # let site = "http://demo.subsonic.org";
+/// # let user = "guest3";
+/// # let password = "guest";
+/// let client = AsyncClient::new(site, user, password)?;
+///
+/// let work = Song::random_async(&client, 20)
+///     .map(|songs| println!("fetched {} songs", songs.len()))
+///     .map_err(|e| eprintln!("{}", e));
+/// // Hand `work` to a tokio runtime to drive it.
+/// # Ok(())
+/// # }
+/// # fn main() { }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AsyncClient {
+    url: Url,
+    auth: Auth,
+    reqclient: ReqwestClient,
+    /// Version that the `AsyncClient` supports.
+    pub ver: Version,
+    /// Version that the `AsyncClient` is targeting; currently only has an
+    /// effect on the authentication method.
+    pub target_ver: Version,
+    server: ServerState,
+}
+
+impl AsyncClient {
+    /// Constructs a client to interact with a Subsonic instance.
+    ///
+    /// See [`Client::new`](./struct.Client.html#method.new) for the accepted
+    /// URLs.
+    pub fn new(url: &str, user: &str, password: &str) -> Result<AsyncClient> {
+        AsyncClient::with_auth(url, Auth::password(user, password))
+    }
+
+    /// Constructs a client that authenticates with the given method.
+    ///
+    /// See [`Client::with_auth`](./struct.Client.html#method.with_auth).
+    pub fn with_auth(url: &str, auth: Auth) -> Result<AsyncClient> {
+        let url = client::base_url(url)?;
+        let ver = Version::new(1, 14, 0);
+        let target_ver = ver.clone();
+
+        let reqclient = ReqwestClient::builder()
+            .default_headers(auth.headers()?)
+            .build()?;
+
+        Ok(AsyncClient {
+            url,
+            auth,
+            reqclient,
+            ver,
+            target_ver,
+            server: ServerState::default(),
+        })
+    }
+
+    /// Adjusts the client to target a specific version.
+    ///
+    /// See [`Client::with_target`] for details.
+    ///
+    /// [`Client::with_target`]: ./struct.Client.html#method.with_target
+    pub fn with_target(self, ver: Version) -> AsyncClient {
+        let mut cli = self;
+        cli.target_ver = ver;
+        cli
+    }
+
+    /// Pings the server and targets the version of the API it implements
+    /// without blocking.
+    ///
+    /// See [`Client::handshake`] for details.
+    ///
+    /// [`Client::handshake`]: ./struct.Client.html#method.handshake
+    pub fn handshake(self) -> SunkFuture<AsyncClient> {
+        let mut cli = self;
+        Box::new(cli.ping().then(move |first| {
+            let server = match cli.server.server().and_then(|s| s.version) {
+                Some(version) => version,
+                None => return Either::A(future::result(first.map(|_| cli))),
+            };
+
+            let target = cmp::min(cli.ver.clone(), server);
+            let retarget = target != cli.target_ver;
+            cli.target_ver = target;
+            match first {
+                Err(Error::Api(_)) if retarget => Either::B(cli.ping().map(|_| cli)),
+                first => Either::A(future::result(first.map(|_| cli))),
+            }
+        }))
+    }
+
+    /// Internal helper function to construct a URL when the actual fetching is
+    /// not required.
+    ///
+    /// Fails if the server is known to be too old for the endpoint.
+    pub(crate) fn build_url(&self, query: &str, args: Query) -> Result<String> {
+        self.server.check(query)?;
+        let auth = self.auth.to_url(&self.target_ver, self.server.tokens());
+        client::build_url(&self.url, &auth, query, args)
+    }
+
+    /// Issues a request to the Subsonic server.
+    ///
+    /// See [`Client`] for the errors that may be returned.
+    ///
+    /// [`Client`]: ./struct.Client.html
+    pub(crate) fn get(&self, query: &str, args: Query) -> SunkFuture<serde_json::Value> {
+        let cli = self.clone();
+        let query = query.to_string();
+        let retry = args.clone();
+        Box::new(self.fetch(&query, args).or_else(move |err| match err {
+            Error::Api(ApiError::Ldap) if cli.server.fall_back_to_password(&cli.auth) => {
+                Either::A(cli.fetch(&query, retry))
+            }
+            err => Either::B(future::err(err)),
+        }))
+    }
+
+    fn fetch(&self, query: &str, args: Query) -> SunkFuture<serde_json::Value> {
+        let uri: Url = match self.build_url(query, args) {
+            Ok(u) => u.parse().unwrap(),
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        info!("Connecting to {}", uri);
+        let server = self.server.clone();
+        Box::new(
+            self.reqclient
+                .get(uri)
+                .send()
+                .map_err(Error::from)
+                .and_then(move |mut res| {
+                    if res.status().is_success() {
+                        Either::A(res.json::<Response>().map_err(Error::from).and_then(
+                            move |res| {
+                                server.record(res.server());
+                                res.into_result()
+                            },
+                        ))
+                    } else {
+                        Either::B(future::err(Error::Connection(res.status())))
+                    }
+                }),
+        )
+    }
+
+    /// Fetches an unprocessed response from the server rather than a JSON- or
+    /// XML-parsed one.
+    pub(crate) fn get_raw(&self, query: &str, args: Query) -> SunkFuture<String> {
+        let uri: Url = match self.build_url(query, args) {
+            Ok(u) => u.parse().unwrap(),
+            Err(e) => return Box::new(future::err(e)),
+        };
+        Box::new(
+            self.reqclient
+                .get(uri)
+                .send()
+                .and_then(|mut res| res.text())
+                .map_err(Error::from),
+        )
+    }
+
+    /// Returns a response as a vector of bytes rather than serialising it.
+    pub(crate) fn get_bytes(&self, query: &str, args: Query) -> SunkFuture<Vec<u8>> {
+        Box::new(self.get_chunks(query, args).concat2())
+    }
+
+    /// Returns a response as a stream of chunks of its body rather than
+    /// buffering it.
+    ///
+    /// The stream will error if the server responds with an API error in
+    /// place of the requested media.
+    pub(crate) fn get_chunks(&self, query: &str, args: Query) -> SunkStream<Vec<u8>> {
+        match self.build_url(query, args) {
+            Ok(u) => self.send_chunks(u.parse().unwrap()),
+            Err(e) => Box::new(future::err(e).into_stream()),
+        }
+    }
+
+    fn send_chunks(&self, uri: Url) -> SunkStream<Vec<u8>> {
+        Box::new(
+            self.reqclient
+                .get(uri)
+                .send()
+                .map_err(Error::from)
+                .and_then(check_media)
+                .map(|res| {
+                    res.into_body()
+                        .map(|chunk| chunk.to_vec())
+                        .map_err(Error::from)
+                })
+                .flatten_stream(),
+        )
+    }
+
+    /// Returns the raw bytes of a HLS slice.
+    pub fn hls_bytes(&self, hls: &Hls) -> SunkFuture<Vec<u8>> {
+        Box::new(self.hls_chunks(hls).concat2())
+    }
+
+    /// Returns a stream of chunks of the bytes of a HLS slice, without
+    /// buffering the slice in memory.
+    pub fn hls_chunks(&self, hls: &Hls) -> SunkStream<Vec<u8>> {
+        match client::resolve_url(&self.url, &hls.url) {
+            Ok(url) => self.send_chunks(url),
+            Err(e) => Box::new(future::err(e).into_stream()),
+        }
+    }
+
+    /// Tests a connection with the server.
+    pub fn ping(&self) -> SunkFuture<()> {
+        Box::new(self.get("ping", Query::none()).map(|_| ()))
+    }
+
+    /// Returns the details the server sent about itself with the last
+    /// response, or `None` if no request has been made yet.
+    pub fn server_info(&self) -> Option<ServerInfo> {
+        self.server.server()
+    }
+
+    /// Discovers what the server is able to do without blocking.
+    ///
+    /// See [`Client::capabilities`] for details.
+    ///
+    /// [`Client::capabilities`]: ./struct.Client.html#method.capabilities
+    pub fn capabilities(&self) -> SunkFuture<Capabilities> {
+        if let Some(caps) = self.server.capabilities() {
+            return Box::new(future::ok(caps));
+        }
+
+        let known = match self.server.server() {
+            Some(server) => Either::A(future::ok(server)),
+            None => {
+                let state = self.server.clone();
+                Either::B(self.ping().and_then(move |_| {
+                    state
+                        .server()
+                        .ok_or_else(|| Error::Other("server did not describe itself"))
+                }))
+            }
+        };
+
+        let cli = self.clone();
+        Box::new(known.and_then(move |server| {
+            let extensions = if server.open_subsonic {
+                Either::A(
+                    cli.get("getOpenSubsonicExtensions", Query::none())
+                        .and_then(|res| Ok(serde_json::from_value::<Vec<Extension>>(res)?)),
+                )
+            } else {
+                Either::B(future::ok(Vec::new()))
+            };
+
+            extensions.map(move |extensions| {
+                let caps = Capabilities { server, extensions };
+                cli.server.set_capabilities(caps.clone());
+                caps
+            })
+        }))
+    }
+
+    /// Get details about the software license.
+    ///
+    /// See [`Client::check_license`] for details.
+    ///
+    /// [`Client::check_license`]: ./struct.Client.html#method.check_license
+    pub fn check_license(&self) -> SunkFuture<License> {
+        Box::new(
+            self.get("getLicense", Query::none())
+                .and_then(|res| Ok(serde_json::from_value::<License>(res)?)),
+        )
+    }
+
+    /// Initiates a rescan of the media libraries.
+    ///
+    /// # Note
+    ///
+    /// This method was introduced in version 1.15.0. It will not be supported
+    /// on servers with earlier versions of the Subsonic API.
+    pub fn scan_library(&self) -> SunkFuture<()> {
+        Box::new(self.get("startScan", Query::none()).map(|_| ()))
+    }
+
+    /// Gets the status of a scan. Returns the current status for media library
+    /// scanning.
+    ///
+    /// # Note
+    ///
+    /// This method was introduced in version 1.15.0. It will not be supported
+    /// on servers with earlier versions of the Subsonic API.
+    pub fn scan_status(&self) -> SunkFuture<(bool, u64)> {
+        #[derive(Deserialize)]
+        struct ScanStatus {
+            count: u64,
+            scanning: bool,
+        }
+
+        Box::new(
+            self.get("getScanStatus", Query::none())
+                .and_then(|res| Ok(serde_json::from_value::<ScanStatus>(res)?))
+                .map(|sc| (sc.scanning, sc.count)),
+        )
+    }
+
+    /// Returns all configured top-level music folders.
+    pub fn music_folders(&self) -> SunkFuture<Vec<MusicFolder>> {
+        Box::new(
+            self.get("getMusicFolders", Query::none())
+                .and_then(|res| -> Result<_> {
+                    #[allow(non_snake_case)]
+                    let musicFolder = res;
+                    Ok(get_list_as!(musicFolder, MusicFolder))
+                }),
+        )
+    }
+
+    /// Returns all genres.
+    pub fn genres(&self) -> SunkFuture<Vec<Genre>> {
+        Box::new(
+            self.get("getGenres", Query::none())
+                .and_then(|genre| -> Result<_> { Ok(get_list_as!(genre, Genre)) }),
+        )
+    }
+
+    /// Returns all currently playing media on the server.
+    pub fn now_playing(&self) -> SunkFuture<Vec<NowPlaying>> {
+        Box::new(
+            self.get("getNowPlaying", Query::none())
+                .and_then(|entry| -> Result<_> { Ok(get_list_as!(entry, NowPlaying)) }),
+        )
+    }
+
+    /// Searches for lyrics matching the artist and title. Resolves to `None`
+    /// if no lyrics are found.
+    pub fn lyrics<'a, S>(&self, artist: S, title: S) -> SunkFuture<Option<Lyrics>>
+    where
+        S: Into<Option<&'a str>>,
+    {
+        let args = Query::with("artist", artist.into())
+            .arg("title", title.into())
+            .build();
+
+        Box::new(self.get("getLyrics", args).and_then(|res| {
+            if res.get("value").is_some() {
+                Ok(Some(serde_json::from_value(res)?))
+            } else {
+                Ok(None)
+            }
+        }))
+    }
+
+    /// Returns albums, artists and songs matching the given search criteria.
+    ///
+    /// See [`Client::search`] for details.
+    ///
+    /// [`Client::search`]: ./struct.Client.html#method.search
+    pub fn search(
+        &self,
+        query: &str,
+        artist_page: SearchPage,
+        album_page: SearchPage,
+        song_page: SearchPage,
+    ) -> SunkFuture<SearchResult> {
+        let args = client::search_query(query, artist_page, album_page, song_page);
+        Box::new(
+            self.get("search3", args)
+                .and_then(|res| Ok(serde_json::from_value::<SearchResult>(res)?)),
+        )
+    }
+
+    /// Returns a list of all starred artists, albums, and songs.
+    pub fn starred<U>(&self, folder_id: U) -> SunkFuture<SearchResult>
+    where
+        U: Into<Option<MusicFolderId>>,
+    {
+        let args = Query::with("musicFolderId", folder_id.into());
+        Box::new(
+            self.get("getStarred", args)
+                .and_then(|res| Ok(serde_json::from_value::<SearchResult>(res)?)),
+        )
+    }
+
+    /// Returns the play queue last saved by the user.
+    ///
+    /// See [`Client::play_queue`] for details.
+    ///
+    /// [`Client::play_queue`]: ./struct.Client.html#method.play_queue
+    pub fn play_queue(&self) -> SunkFuture<Option<PlayQueue>> {
+        Box::new(
+            self.get("getPlayQueue", Query::none())
+                .and_then(|res| Ok(serde_json::from_value::<Option<PlayQueue>>(res)?)),
+        )
+    }
+
+    /// Saves the user's play queue, replacing any previously saved queue.
+    ///
+    /// See [`Client::save_play_queue`] for details.
+    ///
+    /// [`Client::save_play_queue`]: ./struct.Client.html#method.save_play_queue
+    pub fn save_play_queue<C, P>(&self, songs: &[Song], current: C, position: P) -> SunkFuture<()>
+    where
+        C: Into<Option<SongId>>,
+        P: Into<Option<u64>>,
+    {
+        let args = play_queue::save_query(songs, current.into(), position.into());
+        Box::new(self.get("savePlayQueue", args).map(|_| ()))
+    }
+}
+
+/// Checks that a response carrying raw media is not an error.
+fn check_media(mut res: ReqwestResponse) -> SunkFuture<ReqwestResponse> {
+    if !res.status().is_success() {
+        return Box::new(future::err(Error::Connection(res.status())));
+    }
+    // Subsonic reports errors on media endpoints as a regular response.
+    if response::is_json(res.headers()) {
+        return Box::new(
+            res.json::<Response>()
+                .map_err(Error::from)
+                .and_then(Response::into_result)
+                .and_then(|_| Err(Error::Other("expected media, received a response"))),
+        );
+    }
+    Box::new(future::ok(res))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util;
+
+    #[test]
+    fn build_url_matches_blocking_client() {
+        let site = "http://demo.subsonic.org";
+        let cli = test_util::demo_site()
+            .unwrap()
+            .with_target(Version::new(1, 8, 0));
+        let async_cli = AsyncClient::new(site, "guest3", "guest")
+            .unwrap()
+            .with_target(Version::new(1, 8, 0));
+
+        let args = || Query::with("id", 64).arg("size", 120).build();
+        assert_eq!(
+            cli.build_url("getCoverArt", args()).unwrap(),
+            async_cli.build_url("getCoverArt", args()).unwrap()
+        );
+    }
+}
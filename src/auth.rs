@@ -0,0 +1,324 @@
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::fmt;
+
+use query::Query;
+use {Error, Result, Version};
+
+const SALT_SIZE: usize = 36; // Minimum 6 characters.
+
+/// How a client authenticates itself with the server.
+///
+/// Only [`Password`] and [`HexPassword`] need the account password; the other
+/// methods let it be kept out of the application entirely.
+///
+/// [`Password`]: #variant.Password
+/// [`HexPassword`]: #variant.HexPassword
+///
+/// # Examples
+///
+/// ```no_run
+/// use sunk::{Auth, Client};
+/// # fn run() -> sunk::Result<()> {
+/// # let site = "http://demo.subsonic.org";
+/// let client = Client::with_auth(site, Auth::api_key("3f9a0c7d5e1b"))?;
+/// client.ping()?;
+/// # Ok(())
+/// # }
+/// # fn main() { }
+/// ```
+#[derive(Clone)]
+pub enum Auth {
+    /// A username and password.
+    ///
+    /// The password is sent as a salted token to servers implementing version
+    /// 1.13.0 of the API or later, and as is to older servers. If the server
+    /// refuses a token because the user is authenticated through LDAP, the
+    /// client falls back to sending the password hex-encoded for the rest of
+    /// its life.
+    Password {
+        /// The username.
+        user: String,
+        /// The password.
+        password: String,
+    },
+    /// A username and password, with the password sent hex-encoded behind an
+    /// `enc:` prefix.
+    ///
+    /// This is understood by every server, including those that cannot check
+    /// tokens, but hides the password no better than sending it as is.
+    HexPassword {
+        /// The username.
+        user: String,
+        /// The password.
+        password: String,
+    },
+    /// A token and the salt it was made with, computed ahead of time as the
+    /// MD5 hash of the password followed by the salt.
+    ///
+    /// Requires version 1.13.0 of the API.
+    Token {
+        /// The username.
+        user: String,
+        /// The hex-encoded MD5 token.
+        token: String,
+        /// The salt used to make the token.
+        salt: String,
+    },
+    /// An API key, which stands in for both the username and password.
+    ///
+    /// Requires the OpenSubsonic `apiKeyAuthentication` extension.
+    ApiKey(String),
+    /// A header sent with every request, for servers behind a reverse proxy
+    /// that authenticates requests itself.
+    ///
+    /// Only the username is sent to the server. URLs built for other players,
+    /// such as [`Streamable::stream_url`], do not carry the header.
+    ///
+    /// [`Streamable::stream_url`]: ./trait.Streamable.html#tymethod.stream_url
+    Header {
+        /// The username.
+        user: String,
+        /// The name of the header, such as `Authorization`.
+        name: String,
+        /// The value of the header.
+        value: String,
+    },
+}
+
+impl Auth {
+    /// Authenticates with a username and password.
+    pub fn password(user: &str, password: &str) -> Auth {
+        Auth::Password {
+            user: user.into(),
+            password: password.into(),
+        }
+    }
+
+    /// Authenticates with a username and a hex-encoded password.
+    pub fn hex_password(user: &str, password: &str) -> Auth {
+        Auth::HexPassword {
+            user: user.into(),
+            password: password.into(),
+        }
+    }
+
+    /// Authenticates with a precomputed token and salt.
+    pub fn token(user: &str, token: &str, salt: &str) -> Auth {
+        Auth::Token {
+            user: user.into(),
+            token: token.into(),
+            salt: salt.into(),
+        }
+    }
+
+    /// Authenticates with an OpenSubsonic API key.
+    pub fn api_key(key: &str) -> Auth {
+        Auth::ApiKey(key.into())
+    }
+
+    /// Authenticates through a header checked by a reverse proxy.
+    pub fn header(user: &str, name: &str, value: &str) -> Auth {
+        Auth::Header {
+            user: user.into(),
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
+    /// Returns the username, if one is sent.
+    pub fn user(&self) -> Option<&str> {
+        match *self {
+            Auth::Password { ref user, .. }
+            | Auth::HexPassword { ref user, .. }
+            | Auth::Token { ref user, .. }
+            | Auth::Header { ref user, .. } => Some(user),
+            Auth::ApiKey(_) => None,
+        }
+    }
+
+    /// Builds the query arguments every request carries.
+    ///
+    /// `tokens` is whether the server accepts tokens for the user at all.
+    pub(crate) fn to_url(&self, ver: &Version, tokens: bool) -> String {
+        let mut query = Query::new();
+
+        match *self {
+            Auth::Password {
+                ref user,
+                ref password,
+            } => {
+                query.arg("u", user.as_str());
+                // First md5 support.
+                if !tokens {
+                    query.arg("p", hex_password(password));
+                } else if *ver >= Version::new(1, 13, 0) {
+                    let salt = salt();
+                    query.arg("t", token(password, &salt)).arg("s", salt);
+                } else {
+                    query.arg("p", password.as_str());
+                }
+            }
+            Auth::HexPassword {
+                ref user,
+                ref password,
+            } => {
+                query
+                    .arg("u", user.as_str())
+                    .arg("p", hex_password(password));
+            }
+            Auth::Token {
+                ref user,
+                ref token,
+                ref salt,
+            } => {
+                query
+                    .arg("u", user.as_str())
+                    .arg("t", token.as_str())
+                    .arg("s", salt.as_str());
+            }
+            Auth::ApiKey(ref key) => {
+                query.arg("apiKey", key.as_str());
+            }
+            Auth::Header { ref user, .. } => {
+                query.arg("u", user.as_str());
+            }
+        }
+
+        query
+            .arg("v", ver.to_string())
+            .arg("c", env!("CARGO_PKG_NAME"))
+            .arg("f", "json")
+            .build()
+            .to_string()
+    }
+
+    /// Returns the headers every request carries.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the name or value of an authentication header is invalid.
+    pub(crate) fn headers(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        if let Auth::Header {
+            ref name,
+            ref value,
+            ..
+        } = *self
+        {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| Error::Other("invalid authentication header name"))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|_| Error::Other("invalid authentication header value"))?;
+            headers.insert(name, value);
+        }
+        Ok(headers)
+    }
+}
+
+/// Shows the method and username, but never a secret.
+impl fmt::Debug for Auth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let method = match *self {
+            Auth::Password { .. } => "Password",
+            Auth::HexPassword { .. } => "HexPassword",
+            Auth::Token { .. } => "Token",
+            Auth::ApiKey(_) => "ApiKey",
+            Auth::Header { .. } => "Header",
+        };
+        f.debug_struct("Auth")
+            .field("method", &method)
+            .field("user", &self.user())
+            .finish()
+    }
+}
+
+fn salt() -> String {
+    use rand::{distributions::Alphanumeric, thread_rng, Rng};
+    use std::iter;
+
+    let mut rng = thread_rng();
+    iter::repeat(())
+        .map(|()| rng.sample(Alphanumeric))
+        .take(SALT_SIZE)
+        .collect()
+}
+
+fn token(password: &str, salt: &str) -> String {
+    use md5;
+
+    let pre_t = password.to_string() + salt;
+    format!("{:x}", md5::compute(pre_t.as_bytes()))
+}
+
+fn hex_password(password: &str) -> String {
+    let hex = password
+        .bytes()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    format!("enc:{}", hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(auth: &Auth, ver: &str) -> String {
+        auth.to_url(&ver.parse().unwrap(), true)
+    }
+
+    #[test]
+    fn password_follows_version() {
+        let auth = Auth::password("user", "sesame");
+        assert!(args(&auth, "1.13.0").starts_with("u=user&t="));
+        assert_eq!(
+            args(&auth, "1.12.0"),
+            "u=user&p=sesame&v=1.12.0&c=sunk&f=json"
+        );
+        assert_eq!(
+            auth.to_url(&Version::new(1, 16, 1), false),
+            "u=user&p=enc%3A736573616d65&v=1.16.1&c=sunk&f=json"
+        );
+    }
+
+    #[test]
+    fn credentials_without_password() {
+        assert_eq!(
+            args(&Auth::hex_password("user", "sesame"), "1.16.1"),
+            "u=user&p=enc%3A736573616d65&v=1.16.1&c=sunk&f=json"
+        );
+        assert_eq!(
+            args(
+                &Auth::token("user", &token("sesame", "c19b2d"), "c19b2d"),
+                "1.16.1"
+            ),
+            format!(
+                "u=user&t={}&s=c19b2d&v=1.16.1&c=sunk&f=json",
+                token("sesame", "c19b2d")
+            )
+        );
+        assert_eq!(
+            args(&Auth::api_key("3f9a0c"), "1.16.1"),
+            "apiKey=3f9a0c&v=1.16.1&c=sunk&f=json"
+        );
+    }
+
+    #[test]
+    fn proxy_header() {
+        let auth = Auth::header("user", "Remote-User", "user");
+        assert_eq!(args(&auth, "1.16.1"), "u=user&v=1.16.1&c=sunk&f=json");
+        assert_eq!(auth.headers().unwrap()["remote-user"], "user");
+
+        assert!(Auth::header("user", "Bad Name", "x").headers().is_err());
+        assert!(Auth::password("user", "sesame")
+            .headers()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn debug_hides_secrets() {
+        let debug = format!("{:?}", Auth::password("user", "sesame"));
+        assert!(debug.contains("user"));
+        assert!(!debug.contains("sesame"));
+    }
+}
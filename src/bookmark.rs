@@ -0,0 +1,335 @@
+use serde::de::{self, Deserialize, Deserializer};
+use serde_json::Value;
+use std::result;
+
+#[cfg(feature = "async")]
+use futures::Future;
+use id::MediaId;
+use podcast::Episode;
+use query::Query;
+use video::Video;
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture};
+use {Client, Result, Song};
+
+/// A saved position in a piece of media.
+///
+/// Bookmarks are kept on the server per user, so a position saved on one
+/// device can be resumed from another. Each user has at most one bookmark for
+/// each piece of media; creating a new one replaces the old.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bookmark {
+    /// The bookmarked position, in milliseconds.
+    pub position: u64,
+    /// The user who created the bookmark.
+    #[serde(rename = "username")]
+    pub user: String,
+    /// The comment attached to the bookmark, if any.
+    #[serde(default)]
+    pub comment: String,
+    /// When the bookmark was created.
+    pub created: String,
+    /// When the bookmark was last changed.
+    pub changed: String,
+    /// The bookmarked media.
+    pub entry: BookmarkEntry,
+}
+
+/// A piece of media a [`Bookmark`] was made in.
+///
+/// [`Bookmark`]: ./struct.Bookmark.html
+#[derive(Debug, Clone)]
+pub enum BookmarkEntry {
+    /// A bookmarked song.
+    Song(Song),
+    /// A bookmarked video.
+    Video(Video),
+    /// A bookmarked podcast episode.
+    Episode(Episode),
+}
+
+impl BookmarkEntry {
+    /// Returns the ID of the bookmarked media.
+    pub fn id(&self) -> MediaId {
+        match *self {
+            BookmarkEntry::Song(ref s) => MediaId::from(&s.id),
+            BookmarkEntry::Video(ref v) => MediaId::from(&v.id),
+            BookmarkEntry::Episode(ref e) => match e.stream_id {
+                Some(ref id) => id.clone(),
+                None => MediaId::from(&e.id),
+            },
+        }
+    }
+}
+
+impl Bookmark {
+    /// Lists all bookmarks made by the user.
+    pub fn list(client: &Client) -> Result<Vec<Bookmark>> {
+        let bookmark = client.get("getBookmarks", Query::none())?;
+        Ok(get_list_as!(bookmark, Bookmark))
+    }
+
+    /// Bookmarks a position, in milliseconds, in the media with the given ID.
+    ///
+    /// Any existing bookmark the user has on the media is replaced.
+    pub fn create<'a, I, S>(client: &Client, id: I, position: u64, comment: S) -> Result<()>
+    where
+        I: Into<MediaId>,
+        S: Into<Option<&'a str>>,
+    {
+        let args = create_query(id.into(), position, comment.into());
+        client.get("createBookmark", args)?;
+        Ok(())
+    }
+
+    /// Removes the bookmark from the server.
+    pub fn delete(&self, client: &Client) -> Result<()> {
+        client.get("deleteBookmark", Query::with("id", self.entry.id()))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl Bookmark {
+    /// Lists all bookmarks made by the user without blocking.
+    pub fn list_async(client: &AsyncClient) -> SunkFuture<Vec<Bookmark>> {
+        Box::new(
+            client
+                .get("getBookmarks", Query::none())
+                .and_then(|bookmark| -> Result<_> { Ok(get_list_as!(bookmark, Bookmark)) }),
+        )
+    }
+
+    /// Bookmarks a position in the media with the given ID without blocking.
+    ///
+    /// See [`create`](#method.create) for details.
+    pub fn create_async<'a, I, S>(
+        client: &AsyncClient,
+        id: I,
+        position: u64,
+        comment: S,
+    ) -> SunkFuture<()>
+    where
+        I: Into<MediaId>,
+        S: Into<Option<&'a str>>,
+    {
+        let args = create_query(id.into(), position, comment.into());
+        Box::new(client.get("createBookmark", args).map(|_| ()))
+    }
+
+    /// Removes the bookmark from the server without blocking.
+    pub fn delete_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get("deleteBookmark", Query::with("id", self.entry.id()))
+                .map(|_| ()),
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for BookmarkEntry {
+    fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct _Kind {
+            #[serde(default)]
+            is_video: bool,
+            #[serde(rename = "type")]
+            media_type: Option<String>,
+        }
+
+        let mut raw = Value::deserialize(de)?;
+        let kind = _Kind::deserialize(&raw).map_err(de::Error::custom)?;
+
+        if kind.is_video {
+            Ok(BookmarkEntry::Video(
+                Video::deserialize(raw).map_err(de::Error::custom)?,
+            ))
+        } else if kind.media_type.as_deref() == Some("podcast") {
+            // The entry is the episode's media file, so its ID is the one to
+            // stream it with.
+            if let Some(entry) = raw.as_object_mut() {
+                let id = entry.get("id").cloned().unwrap_or(Value::Null);
+                entry.entry("streamId").or_insert(id);
+            }
+            Ok(BookmarkEntry::Episode(
+                Episode::deserialize(raw).map_err(de::Error::custom)?,
+            ))
+        } else {
+            Ok(BookmarkEntry::Song(
+                Song::deserialize(raw).map_err(de::Error::custom)?,
+            ))
+        }
+    }
+}
+
+fn create_query(id: MediaId, position: u64, comment: Option<&str>) -> Query {
+    Query::with("id", id)
+        .arg("position", position)
+        .arg("comment", comment)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+    use test_util;
+
+    #[test]
+    fn parse_bookmark() {
+        let parsed = serde_json::from_value::<Bookmark>(raw()).unwrap();
+        assert_eq!(parsed.position, 61_000);
+        assert_eq!(parsed.user, "guest3");
+        assert_eq!(parsed.comment, "Chapter 3");
+        match parsed.entry {
+            BookmarkEntry::Song(ref song) => assert_eq!(song.id, "27"),
+            ref e => panic!("expected a song, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn parse_video_and_episode_bookmarks() {
+        let raw = r#"[
+            {
+                "position" : 80000,
+                "username" : "guest3",
+                "created" : "2018-02-03T06:22:27.411Z",
+                "changed" : "2018-02-03T06:22:27.411Z",
+                "entry" : {
+                    "id" : "460",
+                    "parent" : "24",
+                    "isDir" : false,
+                    "title" : "Big Buck Bunny",
+                    "album" : "Movies",
+                    "size" : 52464391,
+                    "contentType" : "video/mp4",
+                    "suffix" : "mp4",
+                    "duration" : 281,
+                    "bitRate" : 1488,
+                    "path" : "Movies/Big Buck Bunny.mp4",
+                    "isVideo" : true,
+                    "created" : "2017-03-12T11:06:30.000Z",
+                    "type" : "video"
+                }
+            },
+            {
+                "position" : 1200000,
+                "username" : "guest3",
+                "created" : "2018-02-04T08:10:00.000Z",
+                "changed" : "2018-02-04T08:10:00.000Z",
+                "entry" : {
+                    "id" : "1022",
+                    "parent" : "1001",
+                    "isDir" : false,
+                    "title" : "Episode 42",
+                    "album" : "The Podcast",
+                    "size" : 41038240,
+                    "contentType" : "audio/mpeg",
+                    "suffix" : "mp3",
+                    "duration" : 2565,
+                    "bitRate" : 128,
+                    "isVideo" : false,
+                    "created" : "2018-01-30T14:00:00.000Z",
+                    "type" : "podcast"
+                }
+            }
+        ]"#;
+        let parsed = serde_json::from_str::<Vec<Bookmark>>(raw).unwrap();
+
+        match parsed[0].entry {
+            BookmarkEntry::Video(ref video) => assert_eq!(video.title, "Big Buck Bunny"),
+            ref e => panic!("expected a video, got {:?}", e),
+        }
+        assert_eq!(parsed[0].entry.id(), "460");
+
+        match parsed[1].entry {
+            BookmarkEntry::Episode(ref episode) => {
+                assert_eq!(episode.title, "Episode 42");
+                assert_eq!(episode.channel_id, None);
+                assert_eq!(episode.status, None);
+                assert_eq!(episode.stream_id, Some(MediaId::from(1022)));
+            }
+            ref e => panic!("expected an episode, got {:?}", e),
+        }
+        assert_eq!(parsed[1].entry.id(), "1022");
+    }
+
+    #[test]
+    fn list_create_and_delete_bookmarks() {
+        let body = format!(r#""bookmarks": {{ "bookmark": [{}] }}"#, raw());
+        let (site, heads) = test_util::serve(vec![
+            test_util::ok_response(""),
+            test_util::ok_response(&body),
+            test_util::ok_response(""),
+            test_util::ok_response(r#""bookmarks": {}"#),
+        ]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+
+        Bookmark::create(&cli, 27, 61_000, "Chapter 3").unwrap();
+        let head = heads.recv().unwrap();
+        assert!(head.contains("/rest/createBookmark?"));
+        assert!(head.contains("&id=27&position=61000&comment=Chapter+3 "));
+
+        let bookmarks = Bookmark::list(&cli).unwrap();
+        assert!(heads.recv().unwrap().contains("/rest/getBookmarks?"));
+        assert_eq!(bookmarks.len(), 1);
+
+        bookmarks[0].delete(&cli).unwrap();
+        let head = heads.recv().unwrap();
+        assert!(head.contains("/rest/deleteBookmark?"));
+        assert!(head.contains("&id=27 "));
+
+        assert!(Bookmark::list(&cli).unwrap().is_empty());
+    }
+
+    #[test]
+    fn bookmark_query() {
+        assert_eq!(
+            create_query(MediaId::from(27), 61_000, Some("Chapter 3")).to_string(),
+            "id=27&position=61000&comment=Chapter+3"
+        );
+        assert_eq!(
+            create_query(MediaId::from(27), 0, None).to_string(),
+            "id=27&position=0"
+        );
+    }
+
+    fn raw() -> serde_json::Value {
+        serde_json::from_str(
+            r#"{
+            "position" : 61000,
+            "username" : "guest3",
+            "comment" : "Chapter 3",
+            "created" : "2018-02-03T06:22:27.411Z",
+            "changed" : "2018-02-03T06:22:27.411Z",
+            "entry" : {
+                "id" : "27",
+                "parent" : "25",
+                "isDir" : false,
+                "title" : "Bellevue Avenue",
+                "album" : "Bellevue",
+                "artist" : "Misteur Valaire",
+                "track" : 1,
+                "coverArt" : "25",
+                "size" : 5400185,
+                "contentType" : "audio/mpeg",
+                "suffix" : "mp3",
+                "duration" : 198,
+                "bitRate" : 216,
+                "path" : "Misteur Valaire/Bellevue/01 - Misteur Valaire - Bellevue Avenue.mp3",
+                "playCount" : 706,
+                "created" : "2017-03-12T11:07:27.000Z",
+                "albumId" : "1",
+                "artistId" : "1",
+                "type" : "music"
+            }
+        }"#,
+        )
+        .unwrap()
+    }
+}
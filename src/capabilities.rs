@@ -0,0 +1,318 @@
+use std::sync::{Arc, RwLock};
+
+use auth::Auth;
+use {Error, Result, Version};
+
+/// Details a server sends about itself with every response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerInfo {
+    /// The version of the Subsonic API the server implements, or `None` if
+    /// the server sent a version that could not be parsed.
+    pub version: Option<Version>,
+    /// Whether the server implements the [OpenSubsonic] extensions to the API.
+    ///
+    /// [OpenSubsonic]: https://opensubsonic.netlify.app
+    pub open_subsonic: bool,
+    /// The name of the server software, such as `navidrome`. Only sent by
+    /// OpenSubsonic servers.
+    pub server_type: Option<String>,
+    /// The version of the server software, as opposed to the version of the
+    /// API. Only sent by OpenSubsonic servers.
+    pub server_version: Option<String>,
+}
+
+/// An OpenSubsonic extension supported by a server.
+///
+/// The names of the well-known extensions are available as associated
+/// constants, such as [`Extension::FORM_POST`].
+///
+/// [`Extension::FORM_POST`]: #associatedconstant.FORM_POST
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Extension {
+    /// The name of the extension.
+    pub name: String,
+    /// The versions of the extension the server implements.
+    #[serde(default)]
+    pub versions: Vec<u32>,
+}
+
+impl Extension {
+    /// Requests may be sent as form-encoded `POST` bodies.
+    pub const FORM_POST: &'static str = "formPost";
+    /// Structured, possibly synced lyrics through `getLyricsBySongId`.
+    pub const SONG_LYRICS: &'static str = "songLyrics";
+    /// Streams and transcodes may start from an offset into the media.
+    pub const TRANSCODE_OFFSET: &'static str = "transcodeOffset";
+    /// Users may authenticate with an API key in place of a password.
+    pub const API_KEY_AUTHENTICATION: &'static str = "apiKeyAuthentication";
+}
+
+/// What a server is able to do: the version of the API it implements, and the
+/// OpenSubsonic extensions it supports.
+///
+/// Fetched with [`Client::capabilities`].
+///
+/// [`Client::capabilities`]: ./struct.Client.html#method.capabilities
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    /// The details the server sent about itself.
+    pub server: ServerInfo,
+    /// The OpenSubsonic extensions the server supports. Always empty for
+    /// servers that only implement the Subsonic API.
+    pub extensions: Vec<Extension>,
+}
+
+impl Capabilities {
+    /// Returns the extension with the given name, if the server supports it.
+    pub fn extension(&self, name: &str) -> Option<&Extension> {
+        self.extensions.iter().find(|e| e.name == name)
+    }
+
+    /// Returns `true` if the server supports any version of the extension.
+    pub fn supports(&self, name: &str) -> bool {
+        self.extension(name).is_some()
+    }
+
+    /// Returns `true` if the server supports the given version of the
+    /// extension.
+    pub fn supports_version(&self, name: &str, version: u32) -> bool {
+        self.extensions
+            .iter()
+            .any(|e| e.name == name && e.versions.contains(&version))
+    }
+
+    /// Returns `true` if the server implements at least the given version of
+    /// the Subsonic API. Always `false` if the server's version is unknown.
+    pub fn supports_api(&self, ver: &Version) -> bool {
+        match self.server.version {
+            Some(ref server) => server >= ver,
+            None => false,
+        }
+    }
+
+    /// Checks that the server supports the extension.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Unsupported` naming the extension if the server does
+    /// not support it.
+    pub fn require(&self, name: &str) -> Result<()> {
+        if self.supports(name) {
+            Ok(())
+        } else {
+            Err(Error::Unsupported(name.to_string()))
+        }
+    }
+}
+
+/// What a client has learned about its server, shared between clones of the
+/// client.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ServerState(Arc<RwLock<State>>);
+
+#[derive(Debug, Default)]
+struct State {
+    server: Option<ServerInfo>,
+    capabilities: Option<Capabilities>,
+    /// Set once the server has refused token authentication for the user.
+    tokens_refused: bool,
+}
+
+impl ServerState {
+    /// Records the details sent with a response.
+    pub(crate) fn record(&self, server: ServerInfo) {
+        let mut state = self.0.write().unwrap();
+        if state.server.as_ref() != Some(&server) {
+            // Anything learned about a different server is stale.
+            state.capabilities = None;
+            state.server = Some(server);
+        }
+    }
+
+    pub(crate) fn server(&self) -> Option<ServerInfo> {
+        self.0.read().unwrap().server.clone()
+    }
+
+    pub(crate) fn capabilities(&self) -> Option<Capabilities> {
+        self.0.read().unwrap().capabilities.clone()
+    }
+
+    pub(crate) fn set_capabilities(&self, capabilities: Capabilities) {
+        self.0.write().unwrap().capabilities = Some(capabilities);
+    }
+
+    /// Returns `true` unless the server has refused token authentication for
+    /// the user.
+    pub(crate) fn tokens(&self) -> bool {
+        !self.0.read().unwrap().tokens_refused
+    }
+
+    /// Stops sending tokens after the server refused one for an LDAP user.
+    ///
+    /// Returns `true` if the request should be retried, which is only the
+    /// case the first time for a client authenticating with a password.
+    pub(crate) fn fall_back_to_password(&self, auth: &Auth) -> bool {
+        let user = match *auth {
+            Auth::Password { ref user, .. } => user,
+            _ => return false,
+        };
+
+        let mut state = self.0.write().unwrap();
+        if state.tokens_refused {
+            return false;
+        }
+        state.tokens_refused = true;
+        warn!(
+            "Token authentication is not supported for LDAP user {}; falling back to \
+             a hex-encoded password, which is as exposed as plaintext over HTTP",
+            user
+        );
+        true
+    }
+
+    /// Checks that the server implements the endpoint, if its version is
+    /// known.
+    ///
+    /// Nothing is checked before the server has sent a response, or if the
+    /// version it sent could not be parsed.
+    pub(crate) fn check(&self, endpoint: &str) -> Result<()> {
+        let server = match self.0.read().unwrap().server {
+            Some(ServerInfo {
+                version: Some(ref version),
+                ..
+            }) => version.clone(),
+            _ => return Ok(()),
+        };
+
+        match introduced_in(endpoint) {
+            Some(required) if server < required => Err(Error::ServerTooOld {
+                endpoint: endpoint.to_string(),
+                required,
+                server,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns the version of the API that introduced an endpoint, for endpoints
+/// newer than those every server can be expected to implement.
+fn introduced_in(endpoint: &str) -> Option<Version> {
+    let (major, minor, patch) = match endpoint {
+        "createShare" | "deleteShare" | "getPodcasts" | "getShares" | "refreshPodcasts"
+        | "setRating" | "updateShare" => (1, 6, 0),
+        "getAlbum" | "getAlbumList2" | "getArtist" | "getArtists" | "getAvatar" | "getSong"
+        | "getStarred" | "getStarred2" | "getUsers" | "getVideos" | "hls" | "search3" | "star"
+        | "unstar" | "updatePlaylist" => (1, 8, 0),
+        "createBookmark"
+        | "createPodcastChannel"
+        | "deleteBookmark"
+        | "deletePodcastChannel"
+        | "deletePodcastEpisode"
+        | "downloadPodcastEpisode"
+        | "getBookmarks"
+        | "getGenres"
+        | "getInternetRadioStations"
+        | "getSongsByGenre" => (1, 9, 0),
+        "updateUser" => (1, 10, 1),
+        "getArtistInfo2" | "getSimilarSongs2" => (1, 11, 0),
+        "getPlayQueue" | "savePlayQueue" => (1, 12, 0),
+        "getNewestPodcasts" | "getTopSongs" => (1, 13, 0),
+        "getAlbumInfo" | "getAlbumInfo2" | "getCaptions" | "getVideoInfo" => (1, 14, 0),
+        "getScanStatus" | "startScan" => (1, 15, 0),
+        "createInternetRadioStation"
+        | "deleteInternetRadioStation"
+        | "updateInternetRadioStation" => (1, 16, 0),
+        _ => return None,
+    };
+    Some(Version::new(major, minor, patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn supported_extensions() {
+        let raw = r#"[
+            { "name": "formPost", "versions": [1] },
+            { "name": "songLyrics", "versions": [1, 2] }
+        ]"#;
+        let caps = Capabilities {
+            server: ServerInfo {
+                version: Some(Version::new(1, 16, 1)),
+                open_subsonic: true,
+                server_type: Some("navidrome".into()),
+                server_version: Some("0.53.3".into()),
+            },
+            extensions: serde_json::from_str(raw).unwrap(),
+        };
+
+        assert!(caps.supports(Extension::FORM_POST));
+        assert!(caps.supports_version(Extension::SONG_LYRICS, 2));
+        assert!(!caps.supports_version(Extension::FORM_POST, 2));
+        assert!(caps.supports_api(&Version::new(1, 16, 0)));
+        assert!(!caps.supports_api(&Version::new(1, 17, 0)));
+        match caps.require(Extension::API_KEY_AUTHENTICATION) {
+            Err(Error::Unsupported(ref name)) => assert_eq!(name, "apiKeyAuthentication"),
+            ref r => panic!("expected the extension to be missing, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn endpoints_check_server_version() {
+        let state = ServerState::default();
+        assert!(state.check("getPlayQueue").is_ok());
+
+        state.record(ServerInfo {
+            version: Some(Version::new(1, 11, 0)),
+            open_subsonic: false,
+            server_type: None,
+            server_version: None,
+        });
+        assert!(state.check("hls").is_ok());
+        assert!(state.check("star").is_ok());
+        assert!(state.check("ping").is_ok());
+        match state.check("getPlayQueue") {
+            Err(Error::ServerTooOld {
+                ref endpoint,
+                required,
+                server,
+            }) => {
+                assert_eq!(endpoint, "getPlayQueue");
+                assert_eq!(required, Version::new(1, 12, 0));
+                assert_eq!(server, Version::new(1, 11, 0));
+            }
+            ref r => panic!("expected the server to be too old, got {:?}", r),
+        }
+
+        state.record(ServerInfo {
+            version: Some(Version::new(1, 7, 0)),
+            open_subsonic: false,
+            server_type: None,
+            server_version: None,
+        });
+        assert!(state.check("setRating").is_ok());
+        for endpoint in &["getStarred", "star", "unstar", "updatePlaylist"] {
+            match state.check(endpoint) {
+                Err(Error::ServerTooOld { required, .. }) => {
+                    assert_eq!(required, Version::new(1, 8, 0), "{}", endpoint)
+                }
+                ref r => panic!("expected {} to need 1.8.0, got {:?}", endpoint, r),
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_version_is_not_checked() {
+        let state = ServerState::default();
+        state.record(ServerInfo {
+            version: None,
+            open_subsonic: false,
+            server_type: None,
+            server_version: None,
+        });
+        assert!(state.check("getPlayQueue").is_ok());
+    }
+}
@@ -0,0 +1,301 @@
+#[cfg(feature = "async")]
+use futures::Future;
+use query::Query;
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture};
+use {Client, Result};
+
+/// A message posted to the server's chat.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatMessage {
+    /// The user who posted the message.
+    #[serde(rename = "username")]
+    pub user: String,
+    /// When the message was posted, in milliseconds since the Unix epoch.
+    pub time: u64,
+    /// The content of the message.
+    pub message: String,
+}
+
+impl ChatMessage {
+    /// Lists the messages in the server's chat.
+    ///
+    /// If `since` is provided, only messages posted after that time (in
+    /// milliseconds since the Unix epoch) are returned.
+    pub fn list<U>(client: &Client, since: U) -> Result<Vec<ChatMessage>>
+    where
+        U: Into<Option<u64>>,
+    {
+        #[allow(non_snake_case)]
+        let chatMessage = client.get("getChatMessages", Query::with("since", since.into()))?;
+        Ok(get_list_as!(chatMessage, ChatMessage))
+    }
+
+    /// Posts a message to the server's chat.
+    pub fn send(client: &Client, message: &str) -> Result<()> {
+        client.get("addChatMessage", Query::with("message", message))?;
+        Ok(())
+    }
+
+    /// Creates a poller to fetch new messages as they are posted.
+    ///
+    /// See [`ChatPoller`] for details.
+    ///
+    /// [`ChatPoller`]: ./struct.ChatPoller.html
+    pub fn poller(client: &Client) -> ChatPoller<'_> {
+        ChatPoller {
+            client,
+            since: None,
+            seen: None,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl ChatMessage {
+    /// Lists the messages in the server's chat without blocking.
+    ///
+    /// See [`list`](#method.list) for details.
+    pub fn list_async<U>(client: &AsyncClient, since: U) -> SunkFuture<Vec<ChatMessage>>
+    where
+        U: Into<Option<u64>>,
+    {
+        Box::new(
+            client
+                .get("getChatMessages", Query::with("since", since.into()))
+                .and_then(|res| -> Result<_> {
+                    #[allow(non_snake_case)]
+                    let chatMessage = res;
+                    Ok(get_list_as!(chatMessage, ChatMessage))
+                }),
+        )
+    }
+
+    /// Posts a message to the server's chat without blocking.
+    pub fn send_async(client: &AsyncClient, message: &str) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get("addChatMessage", Query::with("message", message))
+                .map(|_| ()),
+        )
+    }
+}
+
+/// A helper to fetch only the chat messages posted since it was last polled.
+///
+/// The poller remembers the time of the newest message it has returned, and
+/// asks the server for messages from that time on the next poll. Messages
+/// posted in the same millisecond as the newest one are told apart by their
+/// user and content, so none are lost or repeated. The first poll returns the
+/// full chat history the server holds.
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate sunk;
+/// use sunk::{ChatMessage, Client};
+/// use std::thread;
+/// use std::time::Duration;
+///
+/// # fn run() -> sunk::Result<()> {
+/// # let site = "http://demo.subsonic.org";
+/// # let user = "guest3";
+/// # let password = "guest";
+/// let client = Client::new(site, user, password)?;
+/// let mut chat = ChatMessage::poller(&client);
+///
+/// loop {
+///     for msg in chat.poll()? {
+///         println!("<{}> {}", msg.user, msg.message);
+///     }
+///     thread::sleep(Duration::from_secs(5));
+/// }
+/// # }
+/// # fn main() { }
+/// ```
+#[derive(Debug)]
+pub struct ChatPoller<'a> {
+    client: &'a Client,
+    since: Option<u64>,
+    /// The user and content of the messages returned that were posted at
+    /// `since`, or `None` if every message posted then is to be skipped.
+    seen: Option<Vec<(String, String)>>,
+}
+
+impl<'a> ChatPoller<'a> {
+    /// Skips any messages posted at or before the given time, in milliseconds
+    /// since the Unix epoch.
+    pub fn since(&mut self, time: u64) -> &mut ChatPoller<'a> {
+        self.since = Some(time);
+        self.seen = None;
+        self
+    }
+
+    /// Returns the time of the newest message seen by the poller, if any.
+    pub fn last_seen(&self) -> Option<u64> {
+        self.since
+    }
+
+    /// Fetches the messages posted since the last poll, oldest first.
+    pub fn poll(&mut self) -> Result<Vec<ChatMessage>> {
+        // The server only returns messages after `since`, so ask from a
+        // millisecond earlier to catch any posted alongside the newest one.
+        let since = match self.seen {
+            Some(_) => self.since.map(|t| t.saturating_sub(1)),
+            None => self.since,
+        };
+        let messages = ChatMessage::list(self.client, since)?;
+        Ok(self.accept(messages))
+    }
+
+    fn accept(&mut self, messages: Vec<ChatMessage>) -> Vec<ChatMessage> {
+        let mut new = messages
+            .into_iter()
+            .filter(|m| self.is_new(m))
+            .collect::<Vec<_>>();
+        new.sort_by_key(|m| m.time);
+
+        if let Some(last) = new.last().map(|m| m.time) {
+            if self.since != Some(last) {
+                self.since = Some(last);
+                self.seen = Some(Vec::new());
+            }
+            if let Some(ref mut seen) = self.seen {
+                seen.extend(
+                    new.iter()
+                        .filter(|m| m.time == last)
+                        .map(|m| (m.user.clone(), m.message.clone())),
+                );
+            }
+        }
+        new
+    }
+
+    fn is_new(&self, msg: &ChatMessage) -> bool {
+        match self.since {
+            None => true,
+            Some(t) if msg.time > t => true,
+            Some(t) if msg.time == t => match self.seen {
+                Some(ref seen) => !seen
+                    .iter()
+                    .any(|(user, message)| *user == msg.user && *message == msg.message),
+                None => false,
+            },
+            Some(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+    use test_util;
+
+    #[test]
+    fn parse_chat_message() {
+        let parsed = serde_json::from_value::<Vec<ChatMessage>>(raw()).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].user, "admin");
+        assert_eq!(parsed[0].time, 1_518_310_200_000);
+        assert_eq!(parsed[0].message, "Anyone around?");
+    }
+
+    #[test]
+    fn send_chat_message() {
+        let (site, heads) = test_util::serve(vec![test_util::ok_response("")]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+
+        ChatMessage::send(&cli, "Hi & welcome").unwrap();
+        let head = heads.recv().unwrap();
+        assert!(head.contains("/rest/addChatMessage?"));
+        assert!(head.contains("&message=Hi+%26+welcome "));
+    }
+
+    #[test]
+    fn poller_yields_only_new_messages() {
+        let body = format!(r#""chatMessages": {{ "chatMessage": {} }}"#, raw());
+        let (site, heads) = test_util::serve(vec![
+            test_util::ok_response(&body),
+            // A server that ignores `since` still yields nothing new.
+            test_util::ok_response(&body),
+            test_util::ok_response(r#""chatMessages": {}"#),
+        ]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+        let mut chat = ChatMessage::poller(&cli);
+
+        let first = chat.poll().unwrap();
+        assert!(!heads.recv().unwrap().contains("since="));
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0].message, "Hello");
+        assert_eq!(chat.last_seen(), Some(1_518_310_200_000));
+
+        assert!(chat.poll().unwrap().is_empty());
+        assert!(heads.recv().unwrap().contains("&since=1518310199999 "));
+
+        assert!(chat.poll().unwrap().is_empty());
+        assert_eq!(chat.last_seen(), Some(1_518_310_200_000));
+    }
+
+    #[test]
+    fn poller_keeps_messages_from_the_same_millisecond() {
+        let later = r#"[ {
+            "username" : "admin",
+            "time" : 1518310200000,
+            "message" : "Anyone around?"
+        }, {
+            "username" : "guest3",
+            "time" : 1518310200000,
+            "message" : "Yes"
+        } ]"#;
+        let (site, heads) = test_util::serve(vec![
+            test_util::ok_response(&format!(
+                r#""chatMessages": {{ "chatMessage": {} }}"#,
+                raw()
+            )),
+            test_util::ok_response(&format!(
+                r#""chatMessages": {{ "chatMessage": {} }}"#,
+                later
+            )),
+        ]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+        let mut chat = ChatMessage::poller(&cli);
+
+        assert_eq!(chat.poll().unwrap().len(), 2);
+        heads.recv().unwrap();
+
+        let second = chat.poll().unwrap();
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].user, "guest3");
+        assert_eq!(second[0].message, "Yes");
+        assert_eq!(chat.last_seen(), Some(1_518_310_200_000));
+    }
+
+    #[test]
+    fn since_skips_messages_at_that_time() {
+        let (site, heads) = test_util::serve(vec![test_util::ok_response(&format!(
+            r#""chatMessages": {{ "chatMessage": {} }}"#,
+            raw()
+        ))]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+        let mut chat = ChatMessage::poller(&cli);
+
+        assert!(chat.since(1_518_310_200_000).poll().unwrap().is_empty());
+        assert!(heads.recv().unwrap().contains("&since=1518310200000 "));
+    }
+
+    fn raw() -> serde_json::Value {
+        serde_json::from_str(
+            r#"[ {
+            "username" : "admin",
+            "time" : 1518310200000,
+            "message" : "Anyone around?"
+        }, {
+            "username" : "guest3",
+            "time" : 1518310100000,
+            "message" : "Hello"
+        } ]"#,
+        )
+        .unwrap()
+    }
+}
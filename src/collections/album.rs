@@ -2,11 +2,19 @@ use serde::de::{Deserialize, Deserializer};
 use serde_json;
 use std::{fmt, result};
 
-use query::{Arg, IntoArg, Query};
-use search::SearchPage;
-use {Client, Error, Media, Result, Song};
-
-#[derive(Debug, Clone, Copy)]
+#[cfg(feature = "async")]
+use futures::{future, Future};
+use id::{AlbumId, ArtistId, MusicFolderId};
+use query::Query;
+use search::{self, Pages, SearchPage};
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture};
+use {Client, Error, ImageUrls, Media, Result, Song};
+
+/// The order or selection of albums returned by [`Album::list`].
+///
+/// [`Album::list`]: ./struct.Album.html#method.list
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ListType {
     AlphaByArtist,
     AlphaByName,
@@ -16,6 +24,14 @@ pub enum ListType {
     Random,
     Recent,
     Starred,
+    /// Albums released between two years, inclusive. If `from` is later than
+    /// `to`, the albums are listed newest first.
+    ByYear {
+        from: u64,
+        to: u64,
+    },
+    /// Albums in the given genre.
+    ByGenre(String),
 }
 
 impl fmt::Display for ListType {
@@ -30,6 +46,8 @@ impl fmt::Display for ListType {
             Random => "random",
             Recent => "recent",
             Starred => "starred",
+            ByYear { .. } => "byYear",
+            ByGenre(_) => "byGenre",
         };
         write!(f, "{}", fmt)
     }
@@ -41,24 +59,23 @@ impl Default for ListType {
     }
 }
 
-impl IntoArg for ListType {
-    fn into_arg(self) -> Arg {
-        self.to_string().into_arg()
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct Album {
-    pub id: u64,
+    pub id: AlbumId,
     pub name: String,
     pub artist: Option<String>,
-    artist_id: Option<u64>,
+    artist_id: Option<ArtistId>,
     cover_id: Option<String>,
     pub duration: u64,
     pub year: Option<u64>,
     pub genre: Option<String>,
     pub song_count: u64,
+    /// When the user starred the album, if they have.
+    pub starred: Option<String>,
     songs: Vec<Song>,
+    /// Whether the album came from the ID3 tag endpoints, rather than the
+    /// folder-based ones.
+    id3: bool,
 }
 
 impl Album {
@@ -68,34 +85,134 @@ impl Album {
     ///
     /// Aside from errors the `Client` may cause, the method will error if
     /// there is no album matching the provided ID.
-    pub fn get(client: &Client, id: usize) -> Result<Album> {
-        self::get_album(client, id as u64)
+    pub fn get<I>(client: &Client, id: I) -> Result<Album>
+    where
+        I: Into<AlbumId>,
+    {
+        self::get_album(client, &id.into())
     }
 
     /// Lists all albums on the server. Supports paging.
-    pub fn list(
+    ///
+    /// Albums are taken from every music folder unless `folder_id` is given.
+    pub fn list<U>(
         client: &Client,
-        list_type: ListType,
+        list_type: &ListType,
         page: SearchPage,
-        folder: usize,
-    ) -> Result<Vec<Album>> {
-        self::get_albums(client, list_type, page.count, page.offset, folder)
+        folder_id: U,
+    ) -> Result<Vec<Album>>
+    where
+        U: Into<Option<MusicFolderId>>,
+    {
+        self::get_albums(client, list_type, page, folder_id.into())
+    }
+
+    /// Returns an iterator over every album in the list, from every music
+    /// folder.
+    ///
+    /// Albums are fetched from the server a page at a time as the iterator
+    /// needs them. See [`Pages`] for details.
+    ///
+    /// [`Pages`]: ./search/struct.Pages.html
+    pub fn iter(client: &Client, list_type: ListType) -> Pages<'_, Album> {
+        iter_albums(client, list_type, None)
+    }
+
+    /// Returns an iterator over every album in the list that is in the given
+    /// music folder.
+    ///
+    /// See [`iter`](#method.iter) for details.
+    pub fn iter_in_folder<I>(client: &Client, list_type: ListType, folder_id: I) -> Pages<'_, Album>
+    where
+        I: Into<MusicFolderId>,
+    {
+        iter_albums(client, list_type, Some(folder_id.into()))
     }
 
     /// Returns all songs in the album.
     pub fn songs(&self, client: &Client) -> Result<Vec<Song>> {
         if self.songs.len() as u64 != self.song_count {
-            Ok(self::get_album(client, self.id)?.songs)
+            Ok(self::get_album(client, &self.id)?.songs)
         } else {
             Ok(self.songs.clone())
         }
     }
 
     /// Returns detailed information about the album.
+    ///
+    /// Albums found through ID3 tags are looked up with `getAlbumInfo2`, and
+    /// those found through the folder structure with `getAlbumInfo`.
     pub fn info(&self, client: &Client) -> Result<AlbumInfo> {
-        let res = client.get("getArtistInfo", Query::with("id", self.id))?;
+        let res = client.get(self.info_method(), Query::with("id", &self.id))?;
         Ok(serde_json::from_value(res)?)
     }
+
+    fn info_method(&self) -> &'static str {
+        if self.id3 {
+            "getAlbumInfo2"
+        } else {
+            "getAlbumInfo"
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl Album {
+    /// Returns a single album from the Subsonic server without blocking.
+    ///
+    /// The asynchronous counterpart to [`get`](#method.get).
+    pub fn get_async<I>(client: &AsyncClient, id: I) -> SunkFuture<Album>
+    where
+        I: Into<AlbumId>,
+    {
+        Box::new(
+            client
+                .get("getAlbum", Query::with("id", id.into()))
+                .and_then(|res| Ok(serde_json::from_value::<Album>(res)?)),
+        )
+    }
+
+    /// Lists all albums on the server without blocking.
+    ///
+    /// The asynchronous counterpart to [`list`](#method.list).
+    pub fn list_async<U>(
+        client: &AsyncClient,
+        list_type: &ListType,
+        page: SearchPage,
+        folder_id: U,
+    ) -> SunkFuture<Vec<Album>>
+    where
+        U: Into<Option<MusicFolderId>>,
+    {
+        let args = albums_query(list_type, page, folder_id.into());
+        Box::new(
+            client
+                .get("getAlbumList2", args)
+                .and_then(|album| -> Result<_> { Ok(get_list_as!(album, Album)) }),
+        )
+    }
+
+    /// Returns all songs in the album without blocking.
+    ///
+    /// The asynchronous counterpart to [`songs`](#method.songs).
+    pub fn songs_async(&self, client: &AsyncClient) -> SunkFuture<Vec<Song>> {
+        if self.songs.len() as u64 != self.song_count {
+            Box::new(Album::get_async(client, &self.id).map(|album| album.songs))
+        } else {
+            Box::new(future::ok(self.songs.clone()))
+        }
+    }
+
+    /// Returns detailed information about the album without blocking.
+    ///
+    /// The asynchronous counterpart to [`info`](#method.info).
+    pub fn info_async(&self, client: &AsyncClient) -> SunkFuture<AlbumInfo> {
+        Box::new(
+            client
+                .get(self.info_method(), Query::with("id", &self.id))
+                .and_then(|res| Ok(serde_json::from_value(res)?)),
+        )
+    }
 }
 
 impl fmt::Display for Album {
@@ -124,33 +241,42 @@ impl<'de> Deserialize<'de> for Album {
         #[derive(Debug, Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct _Album {
-            id: String,
+            id: AlbumId,
+            // Folder-based albums are directories, named by their title.
+            #[serde(alias = "title")]
             name: String,
             artist: Option<String>,
-            artist_id: Option<String>,
+            artist_id: Option<ArtistId>,
             cover_art: Option<String>,
+            #[serde(default)]
             song_count: u64,
+            #[serde(default)]
             duration: u64,
-            created: String,
+            created: Option<String>,
+            starred: Option<String>,
             year: Option<u64>,
             genre: Option<String>,
             #[serde(default)]
             song: Vec<Song>,
+            #[serde(default)]
+            is_dir: bool,
         }
 
         let raw = _Album::deserialize(de)?;
 
         Ok(Album {
-            id: raw.id.parse().unwrap(),
+            id: raw.id,
             name: raw.name,
             artist: raw.artist,
-            artist_id: raw.artist_id.map(|i| i.parse().unwrap()),
+            artist_id: raw.artist_id,
             cover_id: raw.cover_art,
             duration: raw.duration,
             year: raw.year,
             genre: raw.genre,
             song_count: raw.song_count,
+            starred: raw.starred,
             songs: raw.song,
+            id3: !raw.is_dir,
         })
     }
 }
@@ -183,12 +309,17 @@ impl Media for Album {
     }
 }
 
+/// Detailed information about an album, provided by last.fm.
 #[derive(Debug)]
 pub struct AlbumInfo {
+    /// A blurb about the album.
     pub notes: String,
+    /// The album's [last.fm](https://last.fm) landing page.
     pub lastfm_url: String,
+    /// The album's [MusicBrainz](https://musicbrainz.org/) ID.
     pub musicbrainz_id: String,
-    pub image_urls: (String, String, String),
+    /// URLs for the album's cover, in the sizes the server knows of.
+    pub image_urls: ImageUrls,
 }
 
 impl<'de> Deserialize<'de> for AlbumInfo {
@@ -199,12 +330,15 @@ impl<'de> Deserialize<'de> for AlbumInfo {
         #[derive(Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct _AlbumInfo {
+            #[serde(default)]
             notes: String,
+            #[serde(default)]
             music_brainz_id: String,
+            #[serde(default)]
             last_fm_url: String,
-            small_image_url: String,
-            medium_image_url: String,
-            large_image_url: String,
+            small_image_url: Option<String>,
+            medium_image_url: Option<String>,
+            large_image_url: Option<String>,
         }
 
         let raw = _AlbumInfo::deserialize(de)?;
@@ -213,41 +347,60 @@ impl<'de> Deserialize<'de> for AlbumInfo {
             notes: raw.notes,
             musicbrainz_id: raw.music_brainz_id,
             lastfm_url: raw.last_fm_url,
-            image_urls: (
-                raw.small_image_url,
-                raw.medium_image_url,
-                raw.large_image_url,
-            ),
+            image_urls: ImageUrls {
+                small: raw.small_image_url,
+                medium: raw.medium_image_url,
+                large: raw.large_image_url,
+            },
         })
     }
 }
 
-fn get_album(client: &Client, id: u64) -> Result<Album> {
+fn get_album(client: &Client, id: &AlbumId) -> Result<Album> {
     let res = client.get("getAlbum", Query::with("id", id))?;
     Ok(serde_json::from_value::<Album>(res)?)
 }
 
-fn get_albums<U>(
+fn get_albums(
     client: &Client,
-    list_type: ListType,
-    size: U,
-    offset: U,
-    folder_id: U,
-) -> Result<Vec<Album>>
-where
-    U: Into<Option<usize>>,
-{
-    let args = Query::new()
-        .arg("type", list_type)
-        .arg("size", size.into())
-        .arg("offset", offset.into())
-        .arg("musicFolderId", folder_id.into())
-        .build();
-
+    list_type: &ListType,
+    page: SearchPage,
+    folder_id: Option<MusicFolderId>,
+) -> Result<Vec<Album>> {
+    let args = albums_query(list_type, page, folder_id);
     let album = client.get("getAlbumList2", args)?;
     Ok(get_list_as!(album, Album))
 }
 
+fn iter_albums(
+    client: &Client,
+    list_type: ListType,
+    folder_id: Option<MusicFolderId>,
+) -> Pages<'_, Album> {
+    Pages::new(search::ALL, move |page| {
+        get_albums(client, &list_type, page, folder_id.clone())
+    })
+}
+
+fn albums_query(list_type: &ListType, page: SearchPage, folder_id: Option<MusicFolderId>) -> Query {
+    let mut query = Query::with("type", list_type.to_string());
+    match *list_type {
+        ListType::ByYear { from, to } => {
+            query.arg("fromYear", from).arg("toYear", to);
+        }
+        ListType::ByGenre(ref genre) => {
+            query.arg("genre", genre.as_str());
+        }
+        _ => (),
+    }
+
+    query
+        .arg("size", page.count)
+        .arg("offset", page.offset)
+        .arg("musicFolderId", folder_id)
+        .build()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -256,7 +409,8 @@ mod tests {
     #[test]
     fn demo_get_albums() {
         let mut srv = test_util::demo_site().unwrap();
-        let albums = get_albums(&mut srv, ListType::AlphaByArtist, None, None, None).unwrap();
+        let albums =
+            get_albums(&mut srv, &ListType::AlphaByArtist, SearchPage::new(), None).unwrap();
 
         assert!(!albums.is_empty())
     }
@@ -265,7 +419,7 @@ mod tests {
     fn parse_album() {
         let parsed = serde_json::from_value::<Album>(raw()).unwrap();
 
-        assert_eq!(parsed.id, 1);
+        assert_eq!(parsed.id, "1");
         assert_eq!(parsed.name, String::from("Bellevue"));
         assert_eq!(parsed.song_count, 9);
     }
@@ -274,11 +428,125 @@ mod tests {
     fn parse_album_deep() {
         let parsed = serde_json::from_value::<Album>(raw()).unwrap();
 
-        assert_eq!(parsed.songs[0].id, 27);
+        assert_eq!(parsed.songs[0].id, "27");
         assert_eq!(parsed.songs[0].title, String::from("Bellevue Avenue"));
         assert_eq!(parsed.songs[0].duration, Some(198));
     }
 
+    #[test]
+    fn iter_albums() {
+        let body = format!(r#""albumList2": {{ "album": [{0}, {0}] }}"#, raw());
+        let (site, heads) = test_util::serve(vec![test_util::ok_response(&body)]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+
+        let albums = Album::iter_in_folder(&cli, ListType::AlphaByName, "2")
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(albums.len(), 2);
+
+        let head = heads.recv().unwrap();
+        assert!(head.contains("/rest/getAlbumList2?"));
+        assert!(head.contains("&type=alphabeticalByName&size=500&offset=0&musicFolderId=2 "));
+    }
+
+    #[test]
+    fn list_by_year_and_genre() {
+        let body = format!(r#""albumList2": {{ "album": [{}] }}"#, raw());
+        let (site, heads) = test_util::serve(vec![
+            test_util::ok_response(&body),
+            test_util::ok_response(&body),
+        ]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+        let page = SearchPage::new();
+
+        let decade = ListType::ByYear {
+            from: 1980,
+            to: 1989,
+        };
+        Album::list(&cli, &decade, page, None).unwrap();
+        let head = heads.recv().unwrap();
+        assert!(head.contains("&type=byYear&fromYear=1980&toYear=1989&size=20&offset=0 "));
+
+        let genre = ListType::ByGenre("Hip-Hop & Rap".into());
+        Album::list(&cli, &genre, page, MusicFolderId::new("3")).unwrap();
+        let head = heads.recv().unwrap();
+        assert!(
+            head.contains("&type=byGenre&genre=Hip-Hop+%26+Rap&size=20&offset=0&musicFolderId=3 ")
+        );
+    }
+
+    #[test]
+    fn info_by_id3_tags() {
+        let (site, heads) = test_util::serve(vec![test_util::ok_response(&format!(
+            r#""albumInfo": {}"#,
+            raw_info()
+        ))]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+        let album = serde_json::from_value::<Album>(raw()).unwrap();
+
+        let info = album.info(&cli).unwrap();
+        assert_eq!(
+            info.notes,
+            "Bellevue is the third album by Misteur Valaire."
+        );
+        assert_eq!(info.musicbrainz_id, "8c8a4a0b-6a63-4a5a-9d4a-6c3a4b5b3d1e");
+        assert_eq!(
+            info.lastfm_url,
+            "https://www.last.fm/music/Misteur+Valaire/Bellevue"
+        );
+        assert_eq!(
+            info.image_urls.large.as_ref().unwrap(),
+            "https://lastfm.freetls.fastly.net/i/u/300x300/1.png"
+        );
+
+        let head = heads.recv().unwrap();
+        assert!(head.contains("/rest/getAlbumInfo2?"));
+        assert!(head.contains("&id=1 "));
+    }
+
+    #[test]
+    fn info_by_folder() {
+        let (site, heads) = test_util::serve(vec![test_util::ok_response(
+            r#""albumInfo": { "notes": "Folder notes" }"#,
+        )]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+        let album = serde_json::from_str::<Album>(
+            r#"{
+            "id" : "11",
+            "parent" : "1",
+            "isDir" : true,
+            "title" : "Bellevue",
+            "artist" : "Misteur Valaire",
+            "coverArt" : "11"
+        }"#,
+        )
+        .unwrap();
+        assert_eq!(album.name, "Bellevue");
+
+        let info = album.info(&cli).unwrap();
+        assert_eq!(info.notes, "Folder notes");
+        assert_eq!(info.musicbrainz_id, "");
+        assert_eq!(info.image_urls, ImageUrls::default());
+
+        let head = heads.recv().unwrap();
+        assert!(head.contains("/rest/getAlbumInfo?"));
+        assert!(head.contains("&id=11 "));
+    }
+
+    fn raw_info() -> serde_json::Value {
+        serde_json::from_str(
+            r#"{
+            "notes" : "Bellevue is the third album by Misteur Valaire.",
+            "musicBrainzId" : "8c8a4a0b-6a63-4a5a-9d4a-6c3a4b5b3d1e",
+            "lastFmUrl" : "https://www.last.fm/music/Misteur+Valaire/Bellevue",
+            "smallImageUrl" : "https://lastfm.freetls.fastly.net/i/u/34s/1.png",
+            "mediumImageUrl" : "https://lastfm.freetls.fastly.net/i/u/64s/1.png",
+            "largeImageUrl" : "https://lastfm.freetls.fastly.net/i/u/300x300/1.png"
+        }"#,
+        )
+        .unwrap()
+    }
+
     fn raw() -> serde_json::Value {
         serde_json::from_str(r#"{
          "id" : "1",
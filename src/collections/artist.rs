@@ -3,17 +3,47 @@ use std::{fmt, result};
 use serde::de::{Deserialize, Deserializer};
 use serde_json;
 
+#[cfg(feature = "async")]
+use futures::{future, Future};
+use id::{ArtistId, MusicFolderId};
 use query::Query;
 use {Album, Client, Error, Media, Result, Song};
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture};
 
 /// Basic information about an artist.
 #[derive(Debug, Clone)]
 pub struct Artist {
-    pub id: usize,
+    pub id: ArtistId,
     pub name: String,
     cover_id: Option<String>,
     albums: Vec<Album>,
     pub album_count: usize,
+    /// When the user starred the artist, if they have.
+    pub starred: Option<String>,
+}
+
+/// The artists on the server, grouped alphabetically.
+///
+/// The server sorts artists ignoring any leading article listed in
+/// `ignored_articles`, so "The Beatles" is grouped under B. Use
+/// [`sort_key`](#method.sort_key) to sort names the same way.
+#[derive(Debug, Clone)]
+pub struct ArtistIndexes {
+    /// The articles the server ignores when sorting, separated by spaces.
+    pub ignored_articles: String,
+    /// The groups of artists, in alphabetical order.
+    pub indexes: Vec<ArtistIndex>,
+}
+
+/// A group of artists sharing the same initial.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArtistIndex {
+    /// The name of the group, usually a single letter.
+    pub name: String,
+    /// The artists in the group.
+    #[serde(rename = "artist", default)]
+    pub artists: Vec<Artist>,
 }
 
 /// Detailed information about an artist.
@@ -25,21 +55,49 @@ pub struct ArtistInfo {
     pub musicbrainz_id: String,
     /// The artist's [last.fm](https://last.fm) landing page.
     pub lastfm_url: String,
-    /// URLs for the artist's image; available in small, medium, and large.
-    pub image_urls: (String, String, String),
+    /// URLs for the artist's image, in the sizes the server knows of.
+    pub image_urls: ImageUrls,
     /// Artists similar to this one. Provided by last.fm.
-    similar_artists: Vec<Artist>,
+    pub similar_artists: Vec<Artist>,
+}
+
+/// URLs for an image in small, medium, and large sizes.
+///
+/// Servers leave out the sizes last.fm does not have.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImageUrls {
+    /// The URL of the small image.
+    pub small: Option<String>,
+    /// The URL of the medium image.
+    pub medium: Option<String>,
+    /// The URL of the large image.
+    pub large: Option<String>,
 }
 
 impl Artist {
-    pub fn get(client: &Client, id: usize) -> Result<Artist> {
-        self::get_artist(client, id)
+    pub fn get<I>(client: &Client, id: I) -> Result<Artist>
+    where
+        I: Into<ArtistId>,
+    {
+        self::get_artist(client, &id.into())
+    }
+
+    /// Lists every artist on the server, grouped alphabetically.
+    ///
+    /// Optionally takes the ID of a music folder to only list the artists in
+    /// that folder.
+    pub fn list<U>(client: &Client, folder_id: U) -> Result<ArtistIndexes>
+    where
+        U: Into<Option<MusicFolderId>>,
+    {
+        let res = client.get("getArtists", Query::with("musicFolderId", folder_id.into()))?;
+        Ok(serde_json::from_value(res)?)
     }
 
     /// Returns a list of albums released by the artist.
     pub fn albums(&self, client: &Client) -> Result<Vec<Album>> {
         if self.albums.len() != self.album_count {
-            Ok(self::get_artist(client, self.id)?.albums)
+            Ok(self::get_artist(client, &self.id)?.albums)
         } else {
             Ok(self.albums.clone())
         }
@@ -47,7 +105,7 @@ impl Artist {
 
     /// Queries last.fm for more information about the artist.
     pub fn info(&self, client: &Client) -> Result<ArtistInfo> {
-        let res = client.get("getArtistInfo", Query::with("id", self.id))?;
+        let res = client.get("getArtistInfo2", Query::with("id", &self.id))?;
         Ok(serde_json::from_value(res)?)
     }
 
@@ -67,11 +125,8 @@ impl Artist {
         B: Into<Option<bool>>,
         U: Into<Option<usize>>,
     {
-        let args = Query::with("id", self.id)
-            .arg("count", count.into())
-            .arg("includeNotPresent", include_not_present.into())
-            .build();
-        let res = serde_json::from_value::<ArtistInfo>(client.get("getArtistInfo", args)?)?;
+        let args = similar_query(&self.id, count.into(), include_not_present.into());
+        let res = serde_json::from_value::<ArtistInfo>(client.get("getArtistInfo2", args)?)?;
         Ok(res.similar_artists)
     }
 
@@ -80,7 +135,7 @@ impl Artist {
     where
         U: Into<Option<usize>>,
     {
-        let args = Query::with("id", self.id)
+        let args = Query::with("id", &self.id)
             .arg("count", count.into())
             .build();
 
@@ -89,6 +144,109 @@ impl Artist {
     }
 }
 
+#[cfg(feature = "async")]
+impl Artist {
+    /// Fetches an artist from the Subsonic server without blocking.
+    ///
+    /// The asynchronous counterpart to [`get`](#method.get).
+    pub fn get_async<I>(client: &AsyncClient, id: I) -> SunkFuture<Artist>
+    where
+        I: Into<ArtistId>,
+    {
+        Box::new(
+            client
+                .get("getArtist", Query::with("id", id.into()))
+                .and_then(|res| Ok(serde_json::from_value::<Artist>(res)?)),
+        )
+    }
+
+    /// Lists every artist on the server without blocking.
+    ///
+    /// The asynchronous counterpart to [`list`](#method.list).
+    pub fn list_async<U>(client: &AsyncClient, folder_id: U) -> SunkFuture<ArtistIndexes>
+    where
+        U: Into<Option<MusicFolderId>>,
+    {
+        Box::new(
+            client
+                .get("getArtists", Query::with("musicFolderId", folder_id.into()))
+                .and_then(|res| Ok(serde_json::from_value(res)?)),
+        )
+    }
+
+    /// Returns a list of albums released by the artist without blocking.
+    ///
+    /// The asynchronous counterpart to [`albums`](#method.albums).
+    pub fn albums_async(&self, client: &AsyncClient) -> SunkFuture<Vec<Album>> {
+        if self.albums.len() != self.album_count {
+            Box::new(Artist::get_async(client, &self.id).map(|artist| artist.albums))
+        } else {
+            Box::new(future::ok(self.albums.clone()))
+        }
+    }
+
+    /// Queries last.fm for more information about the artist without
+    /// blocking.
+    ///
+    /// The asynchronous counterpart to [`info`](#method.info).
+    pub fn info_async(&self, client: &AsyncClient) -> SunkFuture<ArtistInfo> {
+        Box::new(
+            client
+                .get("getArtistInfo2", Query::with("id", &self.id))
+                .and_then(|res| Ok(serde_json::from_value(res)?)),
+        )
+    }
+
+    /// Returns a number of random artists similar to this one without
+    /// blocking.
+    ///
+    /// The asynchronous counterpart to [`similar`](#method.similar).
+    pub fn similar_async<B, U>(
+        &self,
+        client: &AsyncClient,
+        count: U,
+        include_not_present: B,
+    ) -> SunkFuture<Vec<Artist>>
+    where
+        B: Into<Option<bool>>,
+        U: Into<Option<usize>>,
+    {
+        let args = similar_query(&self.id, count.into(), include_not_present.into());
+        Box::new(
+            client
+                .get("getArtistInfo2", args)
+                .and_then(|res| Ok(serde_json::from_value::<ArtistInfo>(res)?))
+                .map(|info| info.similar_artists),
+        )
+    }
+
+    /// Returns the top `count` most played songs released by the artist
+    /// without blocking.
+    ///
+    /// The asynchronous counterpart to [`top_songs`](#method.top_songs).
+    pub fn top_songs_async<U>(&self, client: &AsyncClient, count: U) -> SunkFuture<Vec<Song>>
+    where
+        U: Into<Option<usize>>,
+    {
+        let args = Query::with("id", &self.id)
+            .arg("count", count.into())
+            .build();
+
+        Box::new(
+            client
+                .get("getTopSongs", args)
+                .and_then(|song| -> Result<_> { Ok(get_list_as!(song, Song)) }),
+        )
+    }
+}
+
+fn similar_query(id: &ArtistId, count: Option<usize>, include_not_present: Option<bool>) -> Query {
+    Query::with("id", id)
+        .arg("count", count)
+        .arg("includeNotPresent", include_not_present)
+        .build()
+}
+
 impl<'de> Deserialize<'de> for Artist {
     fn deserialize<D>(de: D) -> ::std::result::Result<Self, D::Error>
     where
@@ -97,10 +255,11 @@ impl<'de> Deserialize<'de> for Artist {
         #[derive(Debug, Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct _Artist {
-            id: String,
+            id: ArtistId,
             name: String,
             cover_art: Option<String>,
             album_count: usize,
+            starred: Option<String>,
             #[serde(default)]
             album: Vec<Album>,
         }
@@ -108,11 +267,12 @@ impl<'de> Deserialize<'de> for Artist {
         let raw = _Artist::deserialize(de)?;
 
         Ok(Artist {
-            id: raw.id.parse().unwrap(),
+            id: raw.id,
             name: raw.name,
             cover_id: raw.cover_art,
             album_count: raw.album_count,
             albums: raw.album,
+            starred: raw.starred,
         })
     }
 }
@@ -145,6 +305,68 @@ impl Media for Artist {
     }
 }
 
+impl ArtistIndexes {
+    /// Returns the name the server sorts an artist by.
+    ///
+    /// A leading article listed in `ignored_articles` is dropped, ignoring
+    /// case, so "The Beatles" sorts as "Beatles". Names without an ignored
+    /// article are returned as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use sunk::ArtistIndexes;
+    /// let index = ArtistIndexes {
+    ///     ignored_articles: "The El La".into(),
+    ///     indexes: vec![],
+    /// };
+    ///
+    /// assert_eq!(index.sort_key("The Beatles"), "Beatles");
+    /// assert_eq!(index.sort_key("Theatre of Tragedy"), "Theatre of Tragedy");
+    /// ```
+    pub fn sort_key<'a>(&self, name: &'a str) -> &'a str {
+        for article in self.ignored_articles.split_whitespace() {
+            let len = article.len();
+            let stripped = match name.get(..len) {
+                Some(head) if head.eq_ignore_ascii_case(article) => &name[len..],
+                _ => continue,
+            };
+            if stripped.starts_with(' ') && !stripped.trim().is_empty() {
+                return stripped.trim_start();
+            }
+        }
+        name
+    }
+
+    /// Returns every artist in the index, in order.
+    pub fn artists(&self) -> Vec<&Artist> {
+        self.indexes.iter().flat_map(|i| &i.artists).collect()
+    }
+}
+
+impl<'de> Deserialize<'de> for ArtistIndexes {
+    fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct _ArtistIndexes {
+            #[serde(default)]
+            ignored_articles: String,
+            #[serde(default)]
+            index: Vec<ArtistIndex>,
+        }
+
+        let raw = _ArtistIndexes::deserialize(de)?;
+
+        Ok(ArtistIndexes {
+            ignored_articles: raw.ignored_articles,
+            indexes: raw.index,
+        })
+    }
+}
+
 impl fmt::Display for Artist {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.name)
@@ -159,12 +381,16 @@ impl<'de> Deserialize<'de> for ArtistInfo {
         #[derive(Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct _ArtistInfo {
+            #[serde(default)]
             biography: String,
+            #[serde(default)]
             music_brainz_id: String,
+            #[serde(default)]
             last_fm_url: String,
-            small_image_url: String,
-            medium_image_url: String,
-            large_image_url: String,
+            small_image_url: Option<String>,
+            medium_image_url: Option<String>,
+            large_image_url: Option<String>,
+            #[serde(default)]
             similar_artist: Vec<Artist>,
         }
 
@@ -174,18 +400,18 @@ impl<'de> Deserialize<'de> for ArtistInfo {
             biography: raw.biography,
             musicbrainz_id: raw.music_brainz_id,
             lastfm_url: raw.last_fm_url,
-            image_urls: (
-                raw.small_image_url,
-                raw.medium_image_url,
-                raw.large_image_url,
-            ),
+            image_urls: ImageUrls {
+                small: raw.small_image_url,
+                medium: raw.medium_image_url,
+                large: raw.large_image_url,
+            },
             similar_artists: raw.similar_artist,
         })
     }
 }
 
 /// Fetches an artist from the Subsonic server.
-fn get_artist(client: &Client, id: usize) -> Result<Artist> {
+fn get_artist(client: &Client, id: &ArtistId) -> Result<Artist> {
     let res = client.get("getArtist", Query::with("id", id))?;
     Ok(serde_json::from_value::<Artist>(res)?)
 }
@@ -199,7 +425,7 @@ mod tests {
     fn parse_artist() {
         let parsed = serde_json::from_value::<Artist>(raw()).unwrap();
 
-        assert_eq!(parsed.id, 1);
+        assert_eq!(parsed.id, "1");
         assert_eq!(parsed.name, String::from("Misteur Valaire"));
         assert_eq!(parsed.album_count, 1);
     }
@@ -209,18 +435,108 @@ mod tests {
         let parsed = serde_json::from_value::<Artist>(raw()).unwrap();
 
         assert_eq!(parsed.albums.len(), parsed.album_count);
-        assert_eq!(parsed.albums[0].id, 1);
+        assert_eq!(parsed.albums[0].id, "1");
         assert_eq!(parsed.albums[0].name, String::from("Bellevue"));
         assert_eq!(parsed.albums[0].song_count, 9);
     }
 
+    #[test]
+    fn list_artists() {
+        let body = r#""artists": {
+            "ignoredArticles" : "The El La Los Las Le Les",
+            "index" : [ {
+                "name" : "B",
+                "artist" : [ {
+                    "id" : "5",
+                    "name" : "The Beatles",
+                    "albumCount" : 13
+                } ]
+            }, {
+                "name" : "M",
+                "artist" : [ {
+                    "id" : "1",
+                    "name" : "Misteur Valaire",
+                    "coverArt" : "ar-1",
+                    "albumCount" : 1
+                } ]
+            } ]
+        }"#;
+        let (site, heads) = test_util::serve(vec![test_util::ok_response(body)]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+
+        let index = Artist::list(&cli, MusicFolderId::new("3")).unwrap();
+        let head = heads.recv().unwrap();
+        assert!(head.contains("/rest/getArtists?"));
+        assert!(head.contains("&musicFolderId=3 "));
+
+        assert_eq!(index.indexes.len(), 2);
+        assert_eq!(index.indexes[0].name, "B");
+        let artists = index.artists();
+        assert_eq!(artists[0].name, "The Beatles");
+        assert_eq!(artists[1].id, "1");
+        assert_eq!(index.sort_key(&artists[0].name), "Beatles");
+    }
+
+    #[test]
+    fn info_by_id3_tags() {
+        let body = r#""artistInfo2": {
+            "biography" : "Misteur Valaire is an electro-jazz band from Sherbrooke.",
+            "musicBrainzId" : "e2a5e5f1-3b4c-4d7e-9f0a-1b2c3d4e5f60",
+            "lastFmUrl" : "https://www.last.fm/music/Misteur+Valaire",
+            "smallImageUrl" : "https://lastfm.freetls.fastly.net/i/u/34s/2.png",
+            "largeImageUrl" : "https://lastfm.freetls.fastly.net/i/u/300x300/2.png",
+            "similarArtist" : [ {
+                "id" : "7",
+                "name" : "Chinese Man",
+                "albumCount" : 4
+            } ]
+        }"#;
+        let (site, heads) = test_util::serve(vec![
+            test_util::ok_response(body),
+            test_util::ok_response(body),
+        ]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+        let artist = serde_json::from_value::<Artist>(raw()).unwrap();
+
+        let info = artist.info(&cli).unwrap();
+        let head = heads.recv().unwrap();
+        assert!(head.contains("/rest/getArtistInfo2?"));
+        assert!(head.contains("&id=1 "));
+
+        assert!(info.biography.starts_with("Misteur Valaire"));
+        assert_eq!(info.lastfm_url, "https://www.last.fm/music/Misteur+Valaire");
+        assert!(info.image_urls.small.is_some());
+        assert_eq!(info.image_urls.medium, None);
+        assert_eq!(info.similar_artists[0].name, "Chinese Man");
+
+        let similar = artist.similar(&cli, 5, false).unwrap();
+        let head = heads.recv().unwrap();
+        assert!(head.contains("/rest/getArtistInfo2?"));
+        assert!(head.contains("&id=1&count=5&includeNotPresent=false "));
+        assert_eq!(similar[0].id, "7");
+    }
+
+    #[test]
+    fn sort_key_ignores_articles() {
+        let index = ArtistIndexes {
+            ignored_articles: "The El La".into(),
+            indexes: vec![],
+        };
+        assert_eq!(index.sort_key("The Beatles"), "Beatles");
+        assert_eq!(index.sort_key("the  pogues"), "pogues");
+        assert_eq!(index.sort_key("La Roux"), "Roux");
+        assert_eq!(index.sort_key("Theatre of Tragedy"), "Theatre of Tragedy");
+        assert_eq!(index.sort_key("The"), "The");
+        assert_eq!(index.sort_key("Éla"), "Éla");
+    }
+
     #[test]
     fn remote_artist_album_list() {
         let mut srv = test_util::demo_site().unwrap();
         let parsed = serde_json::from_value::<Artist>(raw()).unwrap();
         let albums = parsed.albums(&mut srv).unwrap();
 
-        assert_eq!(albums[0].id, 1);
+        assert_eq!(albums[0].id, "1");
         assert_eq!(albums[0].name, String::from("Bellevue"));
         assert_eq!(albums[0].song_count, 9);
     }
@@ -258,5 +574,4 @@ mod tests {
         )
         .unwrap()
     }
-
 }
@@ -0,0 +1,529 @@
+use serde::de::{self, Deserialize, Deserializer};
+use serde_json;
+use std::collections::{HashSet, VecDeque};
+use std::result;
+
+#[cfg(feature = "async")]
+use futures::{future, Future};
+use id::{DirectoryId, MusicFolderId};
+use query::Query;
+use video::Video;
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture};
+use {Client, Result, Song};
+
+/// The top level of a music folder, as returned by [`MusicFolder::indexes`].
+///
+/// [`MusicFolder::indexes`]: ./struct.MusicFolder.html#method.indexes
+#[derive(Debug, Clone)]
+pub struct Indexes {
+    /// When the folder was last changed, in milliseconds since the Unix epoch.
+    pub last_modified: u64,
+    /// The articles the server ignores when sorting, separated by spaces.
+    pub ignored_articles: String,
+    /// Directories the server administrator has marked as shortcuts.
+    pub shortcuts: Vec<Shortcut>,
+    /// The top-level directories of the folder, grouped alphabetically.
+    pub indexes: Vec<Index>,
+    /// Songs placed directly in the root of the folder.
+    pub songs: Vec<Song>,
+}
+
+/// A group of top-level directories sharing the same initial.
+#[derive(Debug, Clone)]
+pub struct Index {
+    /// The name of the group, usually a single letter.
+    pub name: String,
+    /// The directories in the group.
+    pub directories: Vec<Shortcut>,
+}
+
+/// A link to a directory in the server's file tree.
+#[derive(Debug, Clone)]
+pub struct Shortcut {
+    /// The ID of the directory.
+    pub id: DirectoryId,
+    /// The name of the directory.
+    pub name: String,
+}
+
+/// A directory in the server's file tree.
+///
+/// Directories are browsed from the [`Indexes`] of a music folder, and hold
+/// links to their subdirectories along with the media inside them.
+///
+/// [`Indexes`]: ./struct.Indexes.html
+#[derive(Debug, Clone)]
+pub struct Directory {
+    /// The ID of the directory.
+    pub id: DirectoryId,
+    /// The ID of the directory's parent, if it is not the root of a folder.
+    pub parent: Option<DirectoryId>,
+    /// The name of the directory.
+    pub name: String,
+    /// The subdirectories of the directory.
+    pub directories: Vec<Shortcut>,
+    /// The songs in the directory.
+    pub songs: Vec<Song>,
+    /// The videos in the directory.
+    pub videos: Vec<Video>,
+}
+
+impl Shortcut {
+    /// Fetches the directory the shortcut links to.
+    pub fn directory(&self, client: &Client) -> Result<Directory> {
+        Directory::get(client, &self.id)
+    }
+}
+
+#[cfg(feature = "async")]
+impl Shortcut {
+    /// Fetches the directory the shortcut links to without blocking.
+    pub fn directory_async(&self, client: &AsyncClient) -> SunkFuture<Directory> {
+        Directory::get_async(client, &self.id)
+    }
+}
+
+impl Directory {
+    /// Fetches the directory with the given ID.
+    pub fn get<I>(client: &Client, id: I) -> Result<Directory>
+    where
+        I: Into<DirectoryId>,
+    {
+        let res = client.get("getMusicDirectory", Query::with("id", id.into()))?;
+        Ok(serde_json::from_value(res)?)
+    }
+
+    /// Fetches the parent of the directory, or `None` if it is the root of a
+    /// folder.
+    pub fn parent(&self, client: &Client) -> Result<Option<Directory>> {
+        match self.parent {
+            Some(ref id) => Ok(Some(Directory::get(client, id)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns an iterator over every song in the directory and its
+    /// subdirectories.
+    ///
+    /// See [`Walk`] for details.
+    ///
+    /// [`Walk`]: ./struct.Walk.html
+    pub fn walk<'a>(&self, client: &'a Client) -> Walk<'a> {
+        let mut walk = Walk::new(client, self.songs.clone(), &self.directories);
+        walk.visited.insert(self.id.clone());
+        walk
+    }
+}
+
+#[cfg(feature = "async")]
+impl Directory {
+    /// Fetches the directory with the given ID without blocking.
+    pub fn get_async<I>(client: &AsyncClient, id: I) -> SunkFuture<Directory>
+    where
+        I: Into<DirectoryId>,
+    {
+        Box::new(
+            client
+                .get("getMusicDirectory", Query::with("id", id.into()))
+                .and_then(|res| Ok(serde_json::from_value(res)?)),
+        )
+    }
+
+    /// Fetches the parent of the directory without blocking.
+    ///
+    /// See [`parent`](#method.parent) for details.
+    pub fn parent_async(&self, client: &AsyncClient) -> SunkFuture<Option<Directory>> {
+        match self.parent {
+            Some(ref id) => Box::new(Directory::get_async(client, id).map(Some)),
+            None => Box::new(future::ok(None)),
+        }
+    }
+}
+
+/// An iterator over every song beneath a directory or music folder.
+///
+/// The walk is depth-first, yielding the songs in a directory before
+/// descending into its subdirectories. Each directory is fetched from the
+/// server as the walk reaches it, and is visited at most once. A failed fetch
+/// is yielded as an error, and the walk continues with the next directory.
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate sunk;
+/// use sunk::Client;
+///
+/// # fn run() -> sunk::Result<()> {
+/// # let site = "http://demo.subsonic.org";
+/// # let user = "guest3";
+/// # let password = "guest";
+/// let client = Client::new(site, user, password)?;
+///
+/// for folder in client.music_folders()? {
+///     for song in folder.walk(&client)? {
+///         println!("{}", song?.title);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// # fn main() { }
+/// ```
+#[derive(Debug)]
+pub struct Walk<'a> {
+    client: &'a Client,
+    songs: VecDeque<Song>,
+    pending: Vec<DirectoryId>,
+    visited: HashSet<DirectoryId>,
+}
+
+impl<'a> Walk<'a> {
+    pub(crate) fn new(client: &'a Client, songs: Vec<Song>, directories: &[Shortcut]) -> Walk<'a> {
+        Walk {
+            client,
+            songs: songs.into(),
+            pending: directories.iter().rev().map(|d| d.id.clone()).collect(),
+            visited: HashSet::new(),
+        }
+    }
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = Result<Song>;
+
+    fn next(&mut self) -> Option<Result<Song>> {
+        loop {
+            if let Some(song) = self.songs.pop_front() {
+                return Some(Ok(song));
+            }
+
+            let id = self.pending.pop()?;
+            if !self.visited.insert(id.clone()) {
+                continue;
+            }
+
+            match Directory::get(self.client, id) {
+                Ok(dir) => {
+                    self.songs.extend(dir.songs);
+                    self.pending
+                        .extend(dir.directories.iter().rev().map(|d| d.id.clone()));
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Builds the arguments for a `getIndexes` query.
+pub(crate) fn indexes_query(folder_id: &MusicFolderId, if_modified_since: Option<u64>) -> Query {
+    Query::with("musicFolderId", folder_id)
+        .arg("ifModifiedSince", if_modified_since)
+        .build()
+}
+
+/// A child of a directory, before it is sorted into directories, songs and
+/// videos.
+enum Child {
+    Directory(Shortcut),
+    Song(Song),
+    Video(Video),
+}
+
+/// Sorts the children of a directory by kind.
+fn split_children(children: Vec<Child>) -> (Vec<Shortcut>, Vec<Song>, Vec<Video>) {
+    let mut directories = Vec::new();
+    let mut songs = Vec::new();
+    let mut videos = Vec::new();
+    for child in children {
+        match child {
+            Child::Directory(d) => directories.push(d),
+            Child::Song(s) => songs.push(s),
+            Child::Video(v) => videos.push(v),
+        }
+    }
+    (directories, songs, videos)
+}
+
+impl<'de> Deserialize<'de> for Indexes {
+    fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct _Indexes {
+            #[serde(default)]
+            last_modified: u64,
+            #[serde(default)]
+            ignored_articles: String,
+            #[serde(default)]
+            shortcut: Vec<Shortcut>,
+            #[serde(default)]
+            index: Vec<Index>,
+            #[serde(default)]
+            child: Vec<Song>,
+        }
+
+        let raw = _Indexes::deserialize(de)?;
+
+        Ok(Indexes {
+            last_modified: raw.last_modified,
+            ignored_articles: raw.ignored_articles,
+            shortcuts: raw.shortcut,
+            indexes: raw.index,
+            songs: raw.child,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Index {
+    fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct _Index {
+            name: String,
+            #[serde(default)]
+            artist: Vec<Shortcut>,
+        }
+
+        let raw = _Index::deserialize(de)?;
+
+        Ok(Index {
+            name: raw.name,
+            directories: raw.artist,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Shortcut {
+    fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct _Shortcut {
+            id: DirectoryId,
+            name: String,
+        }
+
+        let raw = _Shortcut::deserialize(de)?;
+
+        Ok(Shortcut {
+            id: raw.id,
+            name: raw.name,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Directory {
+    fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct _Directory {
+            id: DirectoryId,
+            parent: Option<DirectoryId>,
+            name: String,
+            #[serde(default)]
+            child: Vec<Child>,
+        }
+
+        let raw = _Directory::deserialize(de)?;
+        let (directories, songs, videos) = split_children(raw.child);
+
+        Ok(Directory {
+            id: raw.id,
+            parent: raw.parent,
+            name: raw.name,
+            directories,
+            songs,
+            videos,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Child {
+    fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct _Kind {
+            id: DirectoryId,
+            #[serde(default)]
+            title: String,
+            #[serde(default)]
+            is_dir: bool,
+            #[serde(default)]
+            is_video: bool,
+        }
+
+        let raw = serde_json::Value::deserialize(de)?;
+        let kind = _Kind::deserialize(&raw).map_err(de::Error::custom)?;
+
+        if kind.is_dir {
+            Ok(Child::Directory(Shortcut {
+                id: kind.id,
+                name: kind.title,
+            }))
+        } else if kind.is_video {
+            Ok(Child::Video(
+                Video::deserialize(raw).map_err(de::Error::custom)?,
+            ))
+        } else {
+            Ok(Child::Song(
+                Song::deserialize(raw).map_err(de::Error::custom)?,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util;
+    use MusicFolder;
+
+    #[test]
+    fn parse_indexes() {
+        let parsed = serde_json::from_value::<Indexes>(raw_indexes()).unwrap();
+        assert_eq!(parsed.last_modified, 1_518_310_200_000);
+        assert_eq!(parsed.ignored_articles, "The El La Los Las Le Les");
+        assert_eq!(parsed.shortcuts[0].name, "Podcasts");
+        assert_eq!(parsed.indexes.len(), 2);
+        assert_eq!(parsed.indexes[1].name, "M");
+        assert_eq!(parsed.indexes[1].directories[0].id, "24");
+        assert!(parsed.songs.is_empty());
+    }
+
+    #[test]
+    fn parse_directory() {
+        let parsed = serde_json::from_value::<Directory>(raw_artist_dir()).unwrap();
+        assert_eq!(parsed.id, "24");
+        assert_eq!(parsed.parent, Some(DirectoryId::from(1)));
+        assert_eq!(parsed.name, "Misteur Valaire");
+        assert_eq!(parsed.directories.len(), 1);
+        assert_eq!(parsed.directories[0].name, "Bellevue");
+        assert_eq!(parsed.songs.len(), 1);
+    }
+
+    #[test]
+    fn walk_music_folder() {
+        let indexes = format!(r#""indexes": {}"#, raw_indexes());
+        let artist = format!(r#""directory": {}"#, raw_artist_dir());
+        let album = format!(r#""directory": {}"#, raw_album_dir());
+        let (site, heads) = test_util::serve(vec![
+            test_util::ok_response(&indexes),
+            test_util::ok_response(r#""directory": { "id": "11", "name": "Empty" }"#),
+            test_util::ok_response(&artist),
+            test_util::ok_response(&album),
+        ]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+        let folder = serde_json::from_str::<MusicFolder>(r#"{"id": 0, "name": "Music"}"#).unwrap();
+
+        let songs = folder
+            .walk(&cli)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        let head = heads.recv().unwrap();
+        assert!(head.contains("/rest/getIndexes?"));
+        assert!(head.contains("&musicFolderId=0 "));
+        for id in &["11", "24", "25"] {
+            let head = heads.recv().unwrap();
+            assert!(head.contains("/rest/getMusicDirectory?"));
+            assert!(head.contains(&format!("&id={} ", id)));
+        }
+
+        let ids = songs.iter().map(|s| s.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["30", "27"]);
+    }
+
+    fn raw_indexes() -> serde_json::Value {
+        serde_json::from_str(
+            r#"{
+            "lastModified" : 1518310200000,
+            "ignoredArticles" : "The El La Los Las Le Les",
+            "shortcut" : [ { "id" : "40", "name" : "Podcasts" } ],
+            "index" : [ {
+                "name" : "E",
+                "artist" : [ { "id" : "11", "name" : "Empty" } ]
+            }, {
+                "name" : "M",
+                "artist" : [ { "id" : "24", "name" : "Misteur Valaire" } ]
+            } ]
+        }"#,
+        )
+        .unwrap()
+    }
+
+    fn raw_artist_dir() -> serde_json::Value {
+        serde_json::from_str(
+            r#"{
+            "id" : "24",
+            "parent" : "1",
+            "name" : "Misteur Valaire",
+            "child" : [ {
+                "id" : "25",
+                "parent" : "24",
+                "isDir" : true,
+                "title" : "Bellevue",
+                "album" : "Bellevue",
+                "artist" : "Misteur Valaire",
+                "coverArt" : "25"
+            }, {
+                "id" : "30",
+                "parent" : "24",
+                "isDir" : false,
+                "title" : "Interlude",
+                "artist" : "Misteur Valaire",
+                "size" : 1200185,
+                "contentType" : "audio/mpeg",
+                "suffix" : "mp3",
+                "duration" : 48,
+                "bitRate" : 216,
+                "path" : "Misteur Valaire/Interlude.mp3",
+                "created" : "2017-03-12T11:07:27.000Z",
+                "type" : "music"
+            } ]
+        }"#,
+        )
+        .unwrap()
+    }
+
+    fn raw_album_dir() -> serde_json::Value {
+        serde_json::from_str(
+            r#"{
+            "id" : "25",
+            "parent" : "24",
+            "name" : "Bellevue",
+            "child" : [ {
+                "id" : "27",
+                "parent" : "25",
+                "isDir" : false,
+                "title" : "Bellevue Avenue",
+                "album" : "Bellevue",
+                "artist" : "Misteur Valaire",
+                "track" : 1,
+                "coverArt" : "25",
+                "size" : 5400185,
+                "contentType" : "audio/mpeg",
+                "suffix" : "mp3",
+                "duration" : 198,
+                "bitRate" : 216,
+                "path" : "Misteur Valaire/Bellevue/01 - Misteur Valaire - Bellevue Avenue.mp3",
+                "playCount" : 706,
+                "created" : "2017-03-12T11:07:27.000Z",
+                "albumId" : "1",
+                "artistId" : "1",
+                "type" : "music"
+            } ]
+        }"#,
+        )
+        .unwrap()
+    }
+}
@@ -1,24 +1,87 @@
 use serde::de::{Deserialize, Deserializer};
 use std::result;
 
+#[cfg(feature = "async")]
+use futures::Future;
+use id::MusicFolderId;
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture};
+use {Client, Result};
+
 mod album;
 mod artist;
+mod directory;
 mod playlist;
 
 pub use self::album::{Album, AlbumInfo, ListType};
-pub use self::artist::{Artist, ArtistInfo};
-pub use self::playlist::Playlist;
+pub use self::artist::{Artist, ArtistIndex, ArtistIndexes, ArtistInfo, ImageUrls};
+pub use self::directory::{Directory, Index, Indexes, Shortcut, Walk};
+pub use self::playlist::{Playlist, PlaylistUpdate};
 
 /// A representation of a music folder on a Subsonic server.
 #[derive(Debug)]
 pub struct MusicFolder {
-    /// The index number of the folder.
-    pub id: usize,
+    /// The ID of the folder.
+    pub id: MusicFolderId,
     /// The name assigned to the folder.
     pub name: String,
     _private: bool,
 }
 
+impl MusicFolder {
+    /// Returns the top-level directories of the folder, grouped
+    /// alphabetically.
+    ///
+    /// If `if_modified_since` is provided (in milliseconds since the Unix
+    /// epoch), the server only lists the directories if the folder has changed
+    /// since then; otherwise the returned `Indexes` are empty.
+    pub fn indexes<U>(&self, client: &Client, if_modified_since: U) -> Result<Indexes>
+    where
+        U: Into<Option<u64>>,
+    {
+        let args = directory::indexes_query(&self.id, if_modified_since.into());
+        let res = client.get("getIndexes", args)?;
+        Ok(::serde_json::from_value(res)?)
+    }
+
+    /// Returns an iterator over every song in the folder.
+    ///
+    /// See [`Walk`] for details.
+    ///
+    /// [`Walk`]: ./struct.Walk.html
+    pub fn walk<'a>(&self, client: &'a Client) -> Result<Walk<'a>> {
+        let indexes = self.indexes(client, None)?;
+        let directories = indexes
+            .indexes
+            .into_iter()
+            .flat_map(|i| i.directories)
+            .collect::<Vec<_>>();
+        Ok(Walk::new(client, indexes.songs, &directories))
+    }
+}
+
+#[cfg(feature = "async")]
+impl MusicFolder {
+    /// Returns the top-level directories of the folder without blocking.
+    ///
+    /// See [`indexes`](#method.indexes) for details.
+    pub fn indexes_async<U>(
+        &self,
+        client: &AsyncClient,
+        if_modified_since: U,
+    ) -> SunkFuture<Indexes>
+    where
+        U: Into<Option<u64>>,
+    {
+        let args = directory::indexes_query(&self.id, if_modified_since.into());
+        Box::new(
+            client
+                .get("getIndexes", args)
+                .and_then(|res| Ok(::serde_json::from_value(res)?)),
+        )
+    }
+}
+
 impl<'de> Deserialize<'de> for MusicFolder {
     fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
     where
@@ -26,13 +89,13 @@ impl<'de> Deserialize<'de> for MusicFolder {
     {
         #[derive(Deserialize)]
         struct _MusicFolder {
-            id: String,
+            id: MusicFolderId,
             name: String,
         }
 
         let raw = _MusicFolder::deserialize(de)?;
         Ok(MusicFolder {
-            id: raw.id.parse().unwrap(),
+            id: raw.id,
             name: raw.name,
             _private: false,
         })
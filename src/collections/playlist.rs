@@ -2,30 +2,302 @@ use serde::de::{Deserialize, Deserializer};
 use serde_json;
 use std::result;
 
+#[cfg(feature = "async")]
+use futures::{future, Future};
+use id::{PlaylistId, SongId};
 use query::Query;
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture};
 use {Client, Error, Media, Result, Song};
 
-#[derive(Debug)]
+/// A playlist on a Subsonic server.
+#[derive(Debug, Clone)]
 pub struct Playlist {
-    id: u64,
-    name: String,
-    duration: u64,
+    /// The ID of the playlist.
+    pub id: PlaylistId,
+    /// The name of the playlist.
+    pub name: String,
+    /// The comment attached to the playlist, if any.
+    pub comment: String,
+    /// The user who owns the playlist.
+    pub owner: String,
+    /// Whether the playlist is visible to other users.
+    pub public: bool,
+    /// The length of the playlist, in seconds.
+    pub duration: u64,
+    /// The number of songs in the playlist.
+    pub song_count: u64,
+    /// When the playlist was created.
+    pub created: String,
+    /// When the playlist was last changed.
+    pub changed: String,
     cover_id: String,
-    song_count: u64,
     songs: Vec<Song>,
 }
 
 impl Playlist {
+    /// Returns a single playlist, including its songs.
+    ///
+    /// # Errors
+    ///
+    /// Aside from errors the `Client` may cause, the method will error if
+    /// there is no playlist matching the provided ID, or if the user is not
+    /// allowed to view it.
+    pub fn get<I>(client: &Client, id: I) -> Result<Playlist>
+    where
+        I: Into<PlaylistId>,
+    {
+        let res = client.get("getPlaylist", Query::with("id", id.into()))?;
+        Ok(serde_json::from_value::<Playlist>(res)?)
+    }
+
+    /// Lists the playlists the user is allowed to play.
+    ///
+    /// If a username is provided, lists the playlists of that user instead.
+    /// Only admins may list the playlists of other users.
+    pub fn list<'a, S>(client: &Client, user: S) -> Result<Vec<Playlist>>
+    where
+        S: Into<Option<&'a str>>,
+    {
+        let playlist = client.get("getPlaylists", Query::with("username", user.into()))?;
+        Ok(get_list_as!(playlist, Playlist))
+    }
+
+    /// Creates a playlist with the given name and songs.
+    ///
+    /// Since API version 1.14.0, the newly created playlist is returned. In
+    /// earlier versions, the server sends an empty response and `None` is
+    /// returned.
+    pub fn create(client: &Client, name: &str, songs: &[SongId]) -> Result<Option<Playlist>> {
+        let res = client.get("createPlaylist", create_query(name, songs))?;
+        parse_created(res)
+    }
+
+    /// Begins an update to the playlist.
+    ///
+    /// No changes are made until the update is [applied]. Only the owner of
+    /// the playlist is allowed to update it.
+    ///
+    /// [applied]: ./struct.PlaylistUpdate.html#method.apply
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// extern crate sunk;
+    /// use sunk::id::SongId;
+    /// use sunk::{Client, Playlist};
+    ///
+    /// # fn run() -> sunk::Result<()> {
+    /// # let site = "http://demo.subsonic.org";
+    /// # let user = "guest3";
+    /// # let password = "guest";
+    /// let client = Client::new(site, user, password)?;
+    /// let playlist = Playlist::get(&client, 1)?;
+    ///
+    /// playlist
+    ///     .update()
+    ///     .name("Sleepier Hits")
+    ///     .add_songs(&[SongId::from(27), SongId::from(28)])
+    ///     .remove_index(0)
+    ///     .apply(&client)?;
+    /// # Ok(())
+    /// # }
+    /// # fn main() { }
+    /// ```
+    pub fn update(&self) -> PlaylistUpdate {
+        PlaylistUpdate::new(&self.id)
+    }
+
+    /// Removes the playlist from the server. Only the owner of the playlist is
+    /// allowed to delete it.
+    pub fn delete(&self, client: &Client) -> Result<()> {
+        client.get("deletePlaylist", Query::with("id", &self.id))?;
+        Ok(())
+    }
+
     /// Fetches the songs contained in a playlist.
     pub fn songs(&self, client: &Client) -> Result<Vec<Song>> {
         if self.songs.len() as u64 != self.song_count {
-            Ok(get_playlist(client, self.id)?.songs)
+            Ok(Playlist::get(client, &self.id)?.songs)
         } else {
             Ok(self.songs.clone())
         }
     }
 }
 
+#[cfg(feature = "async")]
+impl Playlist {
+    /// Returns a single playlist, including its songs, without blocking.
+    pub fn get_async<I>(client: &AsyncClient, id: I) -> SunkFuture<Playlist>
+    where
+        I: Into<PlaylistId>,
+    {
+        Box::new(
+            client
+                .get("getPlaylist", Query::with("id", id.into()))
+                .and_then(|res| Ok(serde_json::from_value::<Playlist>(res)?)),
+        )
+    }
+
+    /// Lists the playlists the user is allowed to play without blocking.
+    ///
+    /// See [`list`](#method.list) for details.
+    pub fn list_async<'a, S>(client: &AsyncClient, user: S) -> SunkFuture<Vec<Playlist>>
+    where
+        S: Into<Option<&'a str>>,
+    {
+        Box::new(
+            client
+                .get("getPlaylists", Query::with("username", user.into()))
+                .and_then(|playlist| -> Result<_> { Ok(get_list_as!(playlist, Playlist)) }),
+        )
+    }
+
+    /// Creates a playlist with the given name and songs without blocking.
+    ///
+    /// See [`create`](#method.create) for details.
+    pub fn create_async(
+        client: &AsyncClient,
+        name: &str,
+        songs: &[SongId],
+    ) -> SunkFuture<Option<Playlist>> {
+        Box::new(
+            client
+                .get("createPlaylist", create_query(name, songs))
+                .and_then(parse_created),
+        )
+    }
+
+    /// Removes the playlist from the server without blocking.
+    pub fn delete_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get("deletePlaylist", Query::with("id", &self.id))
+                .map(|_| ()),
+        )
+    }
+
+    /// Fetches the songs contained in a playlist without blocking.
+    ///
+    /// The asynchronous counterpart to [`songs`](#method.songs).
+    pub fn songs_async(&self, client: &AsyncClient) -> SunkFuture<Vec<Song>> {
+        if self.songs.len() as u64 != self.song_count {
+            Box::new(Playlist::get_async(client, &self.id).map(|playlist| playlist.songs))
+        } else {
+            Box::new(future::ok(self.songs.clone()))
+        }
+    }
+}
+
+fn create_query(name: &str, songs: &[SongId]) -> Query {
+    Query::with("name", name).arg_list("songId", songs).build()
+}
+
+fn parse_created(res: serde_json::Value) -> Result<Option<Playlist>> {
+    if res.is_null() {
+        Ok(None)
+    } else {
+        Ok(Some(serde_json::from_value(res)?))
+    }
+}
+
+/// A set of changes to be made to a playlist.
+///
+/// Created by [`Playlist::update`]; see its documentation for an example.
+///
+/// [`Playlist::update`]: ./struct.Playlist.html#method.update
+#[derive(Clone, Debug, Default)]
+pub struct PlaylistUpdate {
+    id: PlaylistId,
+    name: Option<String>,
+    comment: Option<String>,
+    public: Option<bool>,
+    to_add: Vec<SongId>,
+    to_remove: Vec<usize>,
+}
+
+impl PlaylistUpdate {
+    /// Begins an update to the playlist with the given ID.
+    pub fn new<I>(id: I) -> PlaylistUpdate
+    where
+        I: Into<PlaylistId>,
+    {
+        PlaylistUpdate {
+            id: id.into(),
+            ..PlaylistUpdate::default()
+        }
+    }
+
+    /// Renames the playlist.
+    pub fn name(&mut self, name: &str) -> &mut PlaylistUpdate {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Sets the comment attached to the playlist.
+    pub fn comment(&mut self, comment: &str) -> &mut PlaylistUpdate {
+        self.comment = Some(comment.to_string());
+        self
+    }
+
+    /// Sets whether the playlist is visible to other users.
+    pub fn public(&mut self, public: bool) -> &mut PlaylistUpdate {
+        self.public = Some(public);
+        self
+    }
+
+    /// Appends the song with the given ID to the playlist.
+    pub fn add_song<I>(&mut self, id: I) -> &mut PlaylistUpdate
+    where
+        I: Into<SongId>,
+    {
+        self.to_add.push(id.into());
+        self
+    }
+
+    /// Appends the songs with the given IDs to the playlist.
+    pub fn add_songs(&mut self, ids: &[SongId]) -> &mut PlaylistUpdate {
+        self.to_add.extend_from_slice(ids);
+        self
+    }
+
+    /// Removes the song at the given position in the playlist.
+    ///
+    /// Positions refer to the playlist as it was before the update.
+    pub fn remove_index(&mut self, index: usize) -> &mut PlaylistUpdate {
+        self.to_remove.push(index);
+        self
+    }
+
+    /// Removes the songs at the given positions in the playlist.
+    pub fn remove_indices(&mut self, indices: &[usize]) -> &mut PlaylistUpdate {
+        self.to_remove.extend_from_slice(indices);
+        self
+    }
+
+    /// Pushes the changes to the server.
+    pub fn apply(&self, client: &Client) -> Result<()> {
+        client.get("updatePlaylist", self.query())?;
+        Ok(())
+    }
+
+    /// Pushes the changes to the server without blocking.
+    #[cfg(feature = "async")]
+    pub fn apply_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(client.get("updatePlaylist", self.query()).map(|_| ()))
+    }
+
+    fn query(&self) -> Query {
+        Query::with("playlistId", &self.id)
+            .arg("name", self.name.as_deref())
+            .arg("comment", self.comment.as_deref())
+            .arg("public", self.public)
+            .arg_list("songIdToAdd", &self.to_add)
+            .arg_list("songIndexToRemove", &self.to_remove)
+            .build()
+    }
+}
+
 impl<'de> Deserialize<'de> for Playlist {
     fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
     where
@@ -34,29 +306,37 @@ impl<'de> Deserialize<'de> for Playlist {
         #[derive(Debug, Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct _Playlist {
-            id: String,
+            id: PlaylistId,
             name: String,
             #[serde(default)]
             comment: String,
             owner: String,
+            #[serde(default)]
+            public: bool,
             song_count: u64,
             duration: u64,
             created: String,
             changed: String,
+            #[serde(default)]
             cover_art: String,
             #[serde(default)]
-            songs: Vec<Song>,
+            entry: Vec<Song>,
         }
 
         let raw = _Playlist::deserialize(de)?;
 
         Ok(Playlist {
-            id: raw.id.parse().unwrap(),
+            id: raw.id,
             name: raw.name,
+            comment: raw.comment,
+            owner: raw.owner,
+            public: raw.public,
             duration: raw.duration,
-            cover_id: raw.cover_art,
             song_count: raw.song_count,
-            songs: raw.songs,
+            created: raw.created,
+            changed: raw.changed,
+            cover_id: raw.cover_art,
+            songs: raw.entry,
         })
     }
 }
@@ -67,7 +347,11 @@ impl Media for Playlist {
     }
 
     fn cover_id(&self) -> Option<&str> {
-        Some(self.cover_id.as_ref())
+        if self.has_cover_art() {
+            Some(self.cover_id.as_ref())
+        } else {
+            None
+        }
     }
 
     fn cover_art<U: Into<Option<usize>>>(&self, client: &Client, size: U) -> Result<Vec<u8>> {
@@ -89,68 +373,6 @@ impl Media for Playlist {
     }
 }
 
-fn get_playlists(client: &Client, user: Option<String>) -> Result<Vec<Playlist>> {
-    let playlist = client.get("getPlaylists", Query::with("username", user))?;
-    Ok(get_list_as!(playlist, Playlist))
-}
-
-fn get_playlist(client: &Client, id: u64) -> Result<Playlist> {
-    let res = client.get("getPlaylist", Query::with("id", id))?;
-    Ok(serde_json::from_value::<Playlist>(res)?)
-}
-
-/// Creates a playlist with the given name.
-///
-/// Since API version 1.14.0, the newly created playlist is returned. In earlier
-/// versions, an empty response is returned.
-fn create_playlist(client: &Client, name: String, songs: &[u64]) -> Result<Option<Playlist>> {
-    let args = Query::new()
-        .arg("name", name)
-        .arg_list("songId", songs)
-        .build();
-
-    let res = client.get("createPlaylist", args)?;
-
-    // TODO API is private
-    // if client.api >= "1.14.0".into() {
-    Ok(Some(serde_json::from_value(res)?))
-    // } else {
-    // Ok(None)
-    // }
-}
-
-/// Updates a playlist. Only the owner of the playlist is privileged to do so.
-fn update_playlist<'a, B, S>(
-    client: &Client,
-    id: u64,
-    name: S,
-    comment: S,
-    public: B,
-    to_add: &[u64],
-    to_remove: &[u64],
-) -> Result<()>
-where
-    S: Into<Option<&'a str>>,
-    B: Into<Option<bool>>,
-{
-    let args = Query::new()
-        .arg("id", id)
-        .arg("name", name.into())
-        .arg("comment", comment.into())
-        .arg("public", public.into())
-        .arg_list("songIdToAdd", to_add)
-        .arg_list("songIndexToRemove", to_remove)
-        .build();
-
-    client.get("updatePlaylist", args)?;
-    Ok(())
-}
-
-fn delete_playlist(client: &Client, id: u64) -> Result<()> {
-    client.get("deletePlaylist", Query::with("id", id))?;
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -170,6 +392,99 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_playlist_entries() {
+        let parsed = serde_json::from_value::<Playlist>(raw_with_songs()).unwrap();
+        assert_eq!(parsed.name, "Sleep Hits");
+        assert_eq!(parsed.comment, "For the evening");
+        assert!(parsed.public);
+        assert_eq!(parsed.songs.len(), 1);
+        assert_eq!(parsed.songs[0].id, "27");
+    }
+
+    #[test]
+    fn get_playlist_from_fixture() {
+        let body = format!(r#""playlist": {}"#, raw_with_songs());
+        let (site, heads) = test_util::serve(vec![test_util::ok_response(&body)]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+
+        let playlist = Playlist::get(&cli, 1).unwrap();
+        assert!(heads.recv().unwrap().contains("/rest/getPlaylist?"));
+        assert_eq!(playlist.id, "1");
+        assert_eq!(playlist.songs(&cli).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn list_playlists_from_fixture() {
+        let body = format!(r#""playlists": {{ "playlist": [{}] }}"#, raw());
+        let (site, heads) = test_util::serve(vec![test_util::ok_response(&body)]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+
+        let playlists = Playlist::list(&cli, "user").unwrap();
+        assert!(heads.recv().unwrap().contains("&username=user "));
+        assert_eq!(playlists.len(), 1);
+        assert_eq!(playlists[0].owner, "user");
+    }
+
+    #[test]
+    fn create_playlist_from_fixture() {
+        let body = format!(r#""playlist": {}"#, raw_with_songs());
+        let (site, heads) = test_util::serve(vec![
+            test_util::ok_response(&body),
+            test_util::ok_response(""),
+        ]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+
+        let created =
+            Playlist::create(&cli, "Rock #1", &[SongId::from(27), SongId::from(28)]).unwrap();
+        assert!(heads
+            .recv()
+            .unwrap()
+            .contains("&name=Rock+%231&songId=27&songId=28 "));
+        assert_eq!(created.map(|p| p.id), Some(PlaylistId::from(1)));
+
+        // Servers before 1.14.0 respond without the new playlist.
+        let created = Playlist::create(&cli, "Rock #2", &[]).unwrap();
+        assert!(created.is_none());
+    }
+
+    #[test]
+    fn update_and_delete_playlist() {
+        let (site, heads) =
+            test_util::serve(vec![test_util::ok_response(""), test_util::ok_response("")]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+        let playlist = serde_json::from_value::<Playlist>(raw()).unwrap();
+
+        playlist
+            .update()
+            .name("Sleepier Hits")
+            .public(false)
+            .add_song(27)
+            .remove_indices(&[0, 3])
+            .apply(&cli)
+            .unwrap();
+        let head = heads.recv().unwrap();
+        assert!(head.contains("/rest/updatePlaylist?"));
+        assert!(head.contains(
+            "&playlistId=1&name=Sleepier+Hits&public=false&songIdToAdd=27\
+             &songIndexToRemove=0&songIndexToRemove=3 "
+        ));
+
+        let query = playlist
+            .update()
+            .comment("Quiet")
+            .add_songs(&[SongId::from(27), SongId::from(28)])
+            .remove_index(1)
+            .query();
+        assert_eq!(
+            query.to_string(),
+            "playlistId=1&comment=Quiet&songIdToAdd=27&songIdToAdd=28&songIndexToRemove=1"
+        );
+
+        playlist.delete(&cli).unwrap();
+        assert!(heads.recv().unwrap().contains("/rest/deletePlaylist?"));
+    }
+
     fn raw() -> serde_json::Value {
         serde_json::from_str(
             r#"{
@@ -186,4 +501,43 @@ mod tests {
         )
         .unwrap()
     }
+
+    fn raw_with_songs() -> serde_json::Value {
+        serde_json::from_str(
+            r#"{
+            "id" : "1",
+            "name" : "Sleep Hits",
+            "comment" : "For the evening",
+            "owner" : "user",
+            "public" : true,
+            "songCount" : 1,
+            "duration" : 198,
+            "created" : "2018-01-01T14:45:07.464Z",
+            "changed" : "2018-01-01T14:45:07.478Z",
+            "coverArt" : "pl-1",
+            "entry" : [ {
+                "id" : "27",
+                "parent" : "25",
+                "isDir" : false,
+                "title" : "Bellevue Avenue",
+                "album" : "Bellevue",
+                "artist" : "Misteur Valaire",
+                "track" : 1,
+                "coverArt" : "25",
+                "size" : 5400185,
+                "contentType" : "audio/mpeg",
+                "suffix" : "mp3",
+                "duration" : 198,
+                "bitRate" : 216,
+                "path" : "Misteur Valaire/Bellevue/01 - Misteur Valaire - Bellevue Avenue.mp3",
+                "playCount" : 706,
+                "created" : "2017-03-12T11:07:27.000Z",
+                "albumId" : "1",
+                "artistId" : "1",
+                "type" : "music"
+            } ]
+        }"#,
+        )
+        .unwrap()
+    }
 }
@@ -4,6 +4,8 @@ use serde_json;
 use std::convert::From;
 use std::{fmt, io, num, result};
 
+use version::Version;
+
 /// An alias for `sunk`'s error result type.
 pub type Result<T> = result::Result<T, self::Error>;
 
@@ -21,6 +23,10 @@ pub enum Error {
     #[fail(display = "{}", _0)]
     Api(#[cause] ApiError),
 
+    /// Unable to parse a version of the API.
+    #[fail(display = "{}", _0)]
+    Version(#[cause] VersionError),
+
     /// A number conversion failed.
     #[fail(display = "Failed to parse int: {}", _0)]
     Parse(#[cause] num::ParseIntError),
@@ -34,6 +40,25 @@ pub enum Error {
     #[fail(display = "Error serialising: {}", _0)]
     Serde(#[cause] serde_json::Error),
 
+    /// The server implements a version of the API older than the one that
+    /// introduced the endpoint.
+    #[fail(
+        display = "{} needs API version {}, but the server implements {}",
+        endpoint, required, server
+    )]
+    ServerTooOld {
+        /// The endpoint that was called.
+        endpoint: String,
+        /// The version of the API that introduced the endpoint.
+        required: Version,
+        /// The version of the API the server implements.
+        server: Version,
+    },
+    /// The server does not support a feature the request relies on, such as
+    /// an OpenSubsonic extension.
+    #[fail(display = "Server does not support {}", _0)]
+    Unsupported(String),
+
     /// For general, one-off errors.
     #[fail(display = "{}", _0)]
     Other(&'static str),
@@ -57,6 +82,19 @@ pub enum UrlError {
     Address,
 }
 
+/// Possible errors when parsing a [`Version`].
+///
+/// [`Version`]: ./struct.Version.html
+#[derive(Debug, Fail)]
+pub enum VersionError {
+    /// The string was empty.
+    #[fail(display = "Empty version")]
+    Empty,
+    /// The string did not start with a number, or a number was too large.
+    #[fail(display = "Invalid version: {}", _0)]
+    Invalid(String),
+}
+
 /// The possible errors a Subsonic server may return.
 #[derive(Debug, Fail, Clone)]
 pub enum ApiError {
@@ -172,6 +210,7 @@ box_err!(io::Error, Io);
 box_err!(num::ParseIntError, Parse);
 box_err!(serde_json::Error, Serde);
 box_err!(UrlError, Url);
+box_err!(VersionError, Version);
 box_err!(ApiError, Api);
 
 impl From<reqwest::UrlError> for UrlError {
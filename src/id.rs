@@ -0,0 +1,266 @@
+//! Identifiers for the entities on a Subsonic server.
+//!
+//! Subsonic numbers everything it stores, but other servers implementing the
+//! API, such as Navidrome and Gonic, use opaque strings like hex hashes or
+//! `al-12`. IDs are therefore kept as strings, with a separate type for each
+//! kind of entity so that an album's ID can't be passed where a song's is
+//! expected.
+//!
+//! Every ID can be created from a string or a number, so methods taking an ID
+//! accept any of them:
+//!
+//! ```no_run
+//! extern crate sunk;
+//! use sunk::song::Song;
+//! use sunk::Client;
+//!
+//! # fn run() -> sunk::Result<()> {
+//! # let site = "http://demo.subsonic.org";
+//! # let user = "guest3";
+//! # let password = "guest";
+//! let client = Client::new(site, user, password)?;
+//! let song = Song::get(&client, 27)?;
+//! let same = Song::get(&client, "27")?;
+//! let again = Song::get(&client, &song.id)?;
+//! # Ok(())
+//! # }
+//! # fn main() { }
+//! ```
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use std::{fmt, result};
+
+use query::{Arg, IntoArg};
+
+macro_rules! id {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(String);
+
+        impl $name {
+            /// Creates an ID from its string form.
+            pub fn new<S: Into<String>>(id: S) -> $name {
+                $name(id.into())
+            }
+
+            /// Returns the ID as the server sent it.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> $name {
+                $name(id)
+            }
+        }
+
+        impl<'a> From<&'a str> for $name {
+            fn from(id: &'a str) -> $name {
+                $name(id.to_string())
+            }
+        }
+
+        impl From<u64> for $name {
+            fn from(id: u64) -> $name {
+                $name(id.to_string())
+            }
+        }
+
+        impl<'a> From<&'a $name> for $name {
+            fn from(id: &'a $name) -> $name {
+                id.clone()
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl<'a> PartialEq<&'a str> for $name {
+            fn eq(&self, other: &&'a str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                de.deserialize_any(IdVisitor).map($name)
+            }
+        }
+
+        impl IntoArg for $name {
+            fn into_arg(self) -> Arg {
+                self.0.into_arg()
+            }
+        }
+
+        impl<'a> IntoArg for &'a $name {
+            fn into_arg(self) -> Arg {
+                self.0.as_str().into_arg()
+            }
+        }
+    };
+}
+
+id!(
+    /// The ID of a [`Song`](../song/struct.Song.html).
+    SongId
+);
+id!(
+    /// The ID of an [`Album`](../struct.Album.html).
+    AlbumId
+);
+id!(
+    /// The ID of an [`Artist`](../struct.Artist.html).
+    ArtistId
+);
+id!(
+    /// The ID of a [`Playlist`](../struct.Playlist.html).
+    PlaylistId
+);
+id!(
+    /// The ID of a [`Video`](../video/struct.Video.html).
+    VideoId
+);
+id!(
+    /// The ID of an [`AudioTrack`](../video/struct.AudioTrack.html) of a
+    /// video.
+    AudioTrackId
+);
+id!(
+    /// The ID of the [`Captions`](../video/struct.Captions.html) of a video.
+    CaptionsId
+);
+id!(
+    /// The ID of a [`Conversion`](../video/struct.Conversion.html) of a video.
+    ConversionId
+);
+id!(
+    /// The ID of a [`Directory`](../struct.Directory.html).
+    DirectoryId
+);
+id!(
+    /// The ID of a [`Podcast`](../podcast/struct.Podcast.html) channel.
+    PodcastId
+);
+id!(
+    /// The ID of a podcast [`Episode`](../podcast/struct.Episode.html).
+    EpisodeId
+);
+id!(
+    /// The ID of a [`Share`](../struct.Share.html).
+    ShareId
+);
+id!(
+    /// The ID of a [`MusicFolder`](../struct.MusicFolder.html).
+    MusicFolderId
+);
+id!(
+    /// The ID of an internet [`RadioStation`](../struct.RadioStation.html).
+    RadioStationId
+);
+id!(
+    /// The ID of any media the server can stream, bookmark or share.
+    ///
+    /// Endpoints that accept several kinds of media take a `MediaId`, which
+    /// can be made from the ID of a song, video, podcast episode, album or
+    /// directory.
+    MediaId
+);
+
+macro_rules! media_id {
+    ($($from:ident),*) => {$(
+        impl From<$from> for MediaId {
+            fn from(id: $from) -> MediaId {
+                MediaId(id.0)
+            }
+        }
+
+        impl<'a> From<&'a $from> for MediaId {
+            fn from(id: &'a $from) -> MediaId {
+                MediaId(id.0.clone())
+            }
+        }
+    )*};
+}
+
+media_id!(SongId, VideoId, EpisodeId, AlbumId, DirectoryId);
+
+impl From<MediaId> for SongId {
+    fn from(id: MediaId) -> SongId {
+        SongId(id.0)
+    }
+}
+
+impl From<MediaId> for VideoId {
+    fn from(id: MediaId) -> VideoId {
+        VideoId(id.0)
+    }
+}
+
+/// Accepts IDs sent as either strings or numbers.
+struct IdVisitor;
+
+impl<'de> Visitor<'de> for IdVisitor {
+    type Value = String;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a string or numeric ID")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> result::Result<String, E> {
+        Ok(v.to_string())
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> result::Result<String, E> {
+        Ok(v)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> result::Result<String, E> {
+        Ok(v.to_string())
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> result::Result<String, E> {
+        Ok(v.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn parse_string_and_numeric_ids() {
+        let ids = serde_json::from_str::<Vec<AlbumId>>(r#"["al-12", 12, "3f9a0c"]"#).unwrap();
+        assert_eq!(ids[0], "al-12");
+        assert_eq!(ids[1], AlbumId::from(12));
+        assert_eq!(ids[2].as_str(), "3f9a0c");
+    }
+
+    #[test]
+    fn ids_as_args() {
+        let id = SongId::new("tr-1");
+        assert_eq!(::query::Query::with("id", &id).to_string(), "id=tr-1");
+        assert_eq!(MediaId::from(&id), MediaId::new("tr-1"));
+    }
+}
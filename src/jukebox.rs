@@ -1,8 +1,13 @@
 use serde::de::{Deserialize, Deserializer};
 use serde_json;
-use std::result;
+use std::{result, slice};
 
+#[cfg(feature = "async")]
+use futures::Future;
+use id::SongId;
 use query::Query;
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture};
 use {Client, Result, Song};
 
 /// A wrapper on a `Client` to control just the jukebox.
@@ -72,14 +77,11 @@ impl<'a> Jukebox<'a> {
         Jukebox { client }
     }
 
-    fn send_action_with<U>(&self, action: &str, index: U, ids: &[usize]) -> Result<JukeboxStatus>
+    fn send_action_with<U>(&self, action: &str, index: U, ids: &[SongId]) -> Result<JukeboxStatus>
     where
         U: Into<Option<usize>>,
     {
-        let args = Query::with("action", action)
-            .arg("index", index.into())
-            .arg_list("id", ids)
-            .build();
+        let args = action_query(action, index.into(), ids);
         let res = self.client.get("jukeboxControl", args)?;
         Ok(serde_json::from_value(res)?)
     }
@@ -124,7 +126,7 @@ impl<'a> Jukebox<'a> {
 
     /// Adds the song to the jukebox's playlist.
     pub fn add(&self, song: &Song) -> Result<JukeboxStatus> {
-        self.send_action_with("add", None, &[song.id as usize])
+        self.send_action_with("add", None, slice::from_ref(&song.id))
     }
 
     /// Adds a song matching the provided ID to the playlist.
@@ -133,8 +135,11 @@ impl<'a> Jukebox<'a> {
     ///
     /// The method will return an error if a song matching the provided ID
     /// cannot be found.
-    pub fn add_id(&self, id: usize) -> Result<JukeboxStatus> {
-        self.send_action_with("add", None, &[id])
+    pub fn add_id<I>(&self, id: I) -> Result<JukeboxStatus>
+    where
+        I: Into<SongId>,
+    {
+        self.send_action_with("add", None, &[id.into()])
     }
 
     /// Adds all the songs to the jukebox's playlist.
@@ -142,7 +147,7 @@ impl<'a> Jukebox<'a> {
         self.send_action_with(
             "add",
             None,
-            &songs.iter().map(|s| s.id as usize).collect::<Vec<_>>(),
+            &songs.iter().map(|s| s.id.clone()).collect::<Vec<_>>(),
         )
     }
 
@@ -152,7 +157,7 @@ impl<'a> Jukebox<'a> {
     ///
     /// The method will return an error if at least one ID cannot be matched to
     /// a song.
-    pub fn add_all_ids(&self, ids: &[usize]) -> Result<JukeboxStatus> {
+    pub fn add_all_ids(&self, ids: &[SongId]) -> Result<JukeboxStatus> {
         self.send_action_with("add", None, ids)
     }
 
@@ -181,6 +186,137 @@ impl<'a> Jukebox<'a> {
     }
 }
 
+/// An asynchronous wrapper on an `AsyncClient` to control just the jukebox.
+///
+/// The asynchronous counterpart to [`Jukebox`]; every method mirrors the
+/// method of the same name on `Jukebox`, but returns a future rather than
+/// blocking.
+///
+/// [`Jukebox`]: ./struct.Jukebox.html
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct AsyncJukebox<'a> {
+    client: &'a AsyncClient,
+}
+
+#[cfg(feature = "async")]
+impl<'a> AsyncJukebox<'a> {
+    /// Creates a new handler to the jukebox of the client.
+    pub fn start(client: &'a AsyncClient) -> AsyncJukebox<'a> {
+        AsyncJukebox { client }
+    }
+
+    fn send_action_with<U>(
+        &self,
+        action: &str,
+        index: U,
+        ids: &[SongId],
+    ) -> SunkFuture<JukeboxStatus>
+    where
+        U: Into<Option<usize>>,
+    {
+        let args = action_query(action, index.into(), ids);
+        Box::new(
+            self.client
+                .get("jukeboxControl", args)
+                .and_then(|res| Ok(serde_json::from_value(res)?)),
+        )
+    }
+
+    fn send_action(&self, action: &str) -> SunkFuture<JukeboxStatus> {
+        self.send_action_with(action, None, &[])
+    }
+
+    /// Returns the current playlist of the jukebox, as well as its status.
+    pub fn playlist(&self) -> SunkFuture<JukeboxPlaylist> {
+        Box::new(
+            self.client
+                .get("jukeboxControl", Query::with("action", "get"))
+                .and_then(|res| Ok(serde_json::from_value::<JukeboxPlaylist>(res)?)),
+        )
+    }
+
+    /// Returns the status of the jukebox.
+    pub fn status(&self) -> SunkFuture<JukeboxStatus> {
+        self.send_action("status")
+    }
+
+    /// Tells the jukebox to start playing.
+    pub fn play(&self) -> SunkFuture<JukeboxStatus> {
+        self.send_action("start")
+    }
+
+    /// Tells the jukebox to pause playback.
+    pub fn stop(&self) -> SunkFuture<JukeboxStatus> {
+        self.send_action("stop")
+    }
+
+    /// Moves the jukebox's currently playing song to the provided index
+    /// (zero-indexed).
+    pub fn skip_to(&self, n: usize) -> SunkFuture<JukeboxStatus> {
+        self.send_action_with("skip", n, &[])
+    }
+
+    /// Adds the song to the jukebox's playlist.
+    pub fn add(&self, song: &Song) -> SunkFuture<JukeboxStatus> {
+        self.send_action_with("add", None, slice::from_ref(&song.id))
+    }
+
+    /// Adds a song matching the provided ID to the playlist.
+    pub fn add_id<I>(&self, id: I) -> SunkFuture<JukeboxStatus>
+    where
+        I: Into<SongId>,
+    {
+        self.send_action_with("add", None, &[id.into()])
+    }
+
+    /// Adds all the songs to the jukebox's playlist.
+    pub fn add_all(&self, songs: &[Song]) -> SunkFuture<JukeboxStatus> {
+        self.send_action_with(
+            "add",
+            None,
+            &songs.iter().map(|s| s.id.clone()).collect::<Vec<_>>(),
+        )
+    }
+
+    /// Adds multiple songs matching the provided IDs to the playlist.
+    pub fn add_all_ids(&self, ids: &[SongId]) -> SunkFuture<JukeboxStatus> {
+        self.send_action_with("add", None, ids)
+    }
+
+    /// Clears the jukebox's playlist.
+    pub fn clear(&self) -> SunkFuture<JukeboxStatus> {
+        self.send_action("clear")
+    }
+
+    /// Removes the song at the provided index from the playlist.
+    pub fn remove_id(&self, idx: usize) -> SunkFuture<JukeboxStatus> {
+        self.send_action_with("remove", idx, &[])
+    }
+
+    /// Shuffles the jukebox's playlist.
+    pub fn shuffle(&self) -> SunkFuture<JukeboxStatus> {
+        self.send_action("shuffle")
+    }
+
+    /// Sets the jukebox's playback volume.
+    pub fn set_volume(&self, volume: f32) -> SunkFuture<JukeboxStatus> {
+        let args = Query::with("action", "setGain").arg("gain", volume).build();
+        Box::new(
+            self.client
+                .get("jukeboxControl", args)
+                .and_then(|res| Ok(serde_json::from_value(res)?)),
+        )
+    }
+}
+
+fn action_query(action: &str, index: Option<usize>, ids: &[SongId]) -> Query {
+    Query::with("action", action)
+        .arg("index", index)
+        .arg_list("id", ids)
+        .build()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
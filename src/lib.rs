@@ -64,12 +64,12 @@
 //! let album_songs = fav_album.songs(&client)?;
 //!
 //! use std::fs::File;
-//! use std::io::Write;
+//! use std::io;
 //! for song in &album_songs {
-//!     let bytes = song.download(&client)?;
+//!     let mut reader = song.download_reader(&client)?;
 //!     let mut file =
 //!         File::create(song.title.clone() + "." + song.encoding())?;
-//!     file.write(&bytes)?;
+//!     io::copy(&mut reader, &mut file)?;
 //! }
 //!
 //! // I want to find stuff like this song.
@@ -83,6 +83,15 @@
 //! This has the result of many methods requiring an active connection to a
 //! `Client` to fetch more information.
 //!
+//! # Asynchronous usage
+//!
+//! Enabling the `async` feature provides an [`AsyncClient`], built on
+//! reqwest's non-blocking client. Every method that issues a request has an
+//! `_async` counterpart that takes an `AsyncClient` and returns a future
+//! instead of blocking.
+//!
+//! [`AsyncClient`]: ./struct.AsyncClient.html
+//!
 //! # Debugging
 //!
 //! The crate uses [`log`] as its debugging backend. If your crate uses log,
@@ -103,6 +112,8 @@
 
 #[macro_use]
 extern crate failure;
+#[cfg(feature = "async")]
+extern crate futures;
 #[macro_use]
 extern crate log;
 extern crate md5;
@@ -112,35 +123,60 @@ extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde_json;
+extern crate url;
 
 #[macro_use]
 mod macros;
+#[cfg(feature = "async")]
+mod async_client;
+mod auth;
 mod client;
 mod error;
+pub mod id;
 
 mod collections;
 mod media;
 
 mod annotate;
+mod bookmark;
+mod capabilities;
+mod chat;
 mod jukebox;
+mod play_queue;
 mod query;
 mod response;
 pub mod search;
+mod share;
+mod starred;
 mod user;
 mod version;
 
 #[cfg(test)]
 mod test_util;
 
+#[cfg(feature = "async")]
+pub use self::async_client::{AsyncClient, SunkFuture, SunkStream};
+pub use self::auth::Auth;
+pub use self::bookmark::{Bookmark, BookmarkEntry};
+pub use self::capabilities::{Capabilities, Extension, ServerInfo};
+pub use self::chat::{ChatMessage, ChatPoller};
 pub use self::client::Client;
-pub use self::collections::Playlist;
 pub use self::collections::{Album, AlbumInfo, ListType};
-pub use self::collections::{Artist, ArtistInfo};
+pub use self::collections::{Artist, ArtistIndex, ArtistIndexes, ArtistInfo, ImageUrls};
+pub use self::collections::{Directory, Index, Indexes, Shortcut, Walk};
 pub use self::collections::{Genre, MusicFolder};
-pub use self::error::{ApiError, Error, Result, UrlError};
+pub use self::collections::{Playlist, PlaylistUpdate};
+pub use self::error::{ApiError, Error, Result, UrlError, VersionError};
+#[cfg(feature = "async")]
+pub use self::jukebox::AsyncJukebox;
 pub use self::jukebox::{Jukebox, JukeboxPlaylist, JukeboxStatus};
 pub use self::media::{podcast, song, video};
-pub use self::media::{Hls, HlsPlaylist, Media, NowPlaying, RadioStation, Streamable};
+pub use self::media::{
+    Download, Hls, HlsPlaylist, Media, MediaReader, NowPlaying, RadioStation, Streamable,
+};
+pub use self::play_queue::PlayQueue;
+pub use self::share::{Share, ShareEntry};
+pub use self::starred::{Starred, StarredChanges};
 pub use self::user::{User, UserBuilder};
 pub use self::version::Version;
 
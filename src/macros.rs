@@ -3,6 +3,8 @@ macro_rules! get_list_as {
         #[derive(Deserialize)]
         #[allow(non_snake_case)]
         struct List {
+            // Servers omit the list entirely when it is empty.
+            #[serde(default)]
             $f: Vec<$t>,
         }
         ::serde_json::from_value::<List>($f)?.$f
@@ -0,0 +1,326 @@
+use reqwest::StatusCode;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use {Client, Error, Result};
+
+/// Size of the buffer used when copying media into a sink.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Callback reporting the bytes downloaded so far and the expected total.
+type Progress<'a> = Box<dyn FnMut(u64, Option<u64>) + 'a>;
+
+/// A resumable download of a piece of media.
+///
+/// A `Download` is created through [`Streamable::resumable_download`]. If the
+/// sink it is written to already holds part of the media, the download picks
+/// up where it left off by requesting only the remaining bytes from the
+/// server. Servers that ignore the request for a range, or answer it with a
+/// different range, are handled by restarting the download from the
+/// beginning.
+///
+/// When the size of the media is known, the finished download is checked
+/// against it.
+///
+/// [`Streamable::resumable_download`]: ./trait.Streamable.html#method.resumable_download
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate sunk;
+/// use sunk::song::Song;
+/// use sunk::{Client, Streamable};
+///
+/// # fn run() -> sunk::Result<()> {
+/// # let site = "http://demo.subsonic.org";
+/// # let user = "guest3";
+/// # let password = "guest";
+/// let client = Client::new(site, user, password)?;
+/// let song = Song::get(&client, 1)?;
+///
+/// // If `song.mp3` is left over from an interrupted download, only the
+/// // missing bytes will be fetched.
+/// let written = song
+///     .resumable_download(&client)?
+///     .on_progress(|done, total| println!("{} of {:?} bytes", done, total))
+///     .save("song.mp3")?;
+/// # Ok(())
+/// # }
+/// # fn main() { }
+/// ```
+pub struct Download<'a> {
+    client: &'a Client,
+    url: String,
+    size: Option<u64>,
+    progress: Option<Progress<'a>>,
+}
+
+impl<'a> Download<'a> {
+    pub(crate) fn new(client: &'a Client, url: String, size: Option<u64>) -> Download<'a> {
+        Download {
+            client,
+            url,
+            size,
+            progress: None,
+        }
+    }
+
+    /// Returns the expected size of the media in bytes, if known.
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    /// Sets a callback to report the progress of the download.
+    ///
+    /// The callback is given the number of bytes held by the sink so far,
+    /// including any that were present before the download was resumed, and
+    /// the expected size of the media, if known.
+    pub fn on_progress<F>(&mut self, f: F) -> &mut Download<'a>
+    where
+        F: FnMut(u64, Option<u64>) + 'a,
+    {
+        self.progress = Some(Box::new(f));
+        self
+    }
+
+    /// Downloads the media into the sink, resuming from its current end.
+    ///
+    /// Returns the total length of the media written to the sink.
+    ///
+    /// # Errors
+    ///
+    /// Aside from errors the [`Client`] may return, this method errors if the
+    /// sink already holds more bytes than the media, or if the finished
+    /// download does not match the expected size.
+    ///
+    /// If the server restarts the download from the beginning and the size of
+    /// the media is not known, stale bytes may be left at the end of the sink.
+    /// Use [`save`] to have a file truncated to the downloaded length.
+    ///
+    /// [`Client`]: ./struct.Client.html
+    /// [`save`]: #method.save
+    pub fn write_to<W: Write + Seek>(&mut self, sink: &mut W) -> Result<u64> {
+        let existing = sink.seek(SeekFrom::End(0))?;
+        if let Some(size) = self.size {
+            if existing == size {
+                info!("Download of {} already complete", self.url);
+                self.report(existing);
+                return Ok(existing);
+            } else if existing > size {
+                return Err(Error::Other("existing download is larger than the media"));
+            }
+        }
+
+        let mut reader = match self.client.get_range(&self.url, existing) {
+            Ok(reader) => reader,
+            // Nothing left to fetch past the end of the media.
+            Err(Error::Connection(StatusCode::RANGE_NOT_SATISFIABLE))
+                if existing > 0 && self.size.is_none() =>
+            {
+                self.report(existing);
+                return Ok(existing);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let mut written = match reader.offset() {
+            Some(offset) if offset == existing => existing,
+            offset => {
+                if existing > 0 {
+                    warn!(
+                        "Server did not resume from byte {}; restarting download",
+                        existing
+                    );
+                }
+                // The body does not start where it was asked to, nor at the
+                // beginning of the media, so fetch the media from the start.
+                if offset != Some(0) {
+                    reader = self.client.get_range(&self.url, 0)?;
+                    if reader.offset() != Some(0) {
+                        return Err(Error::Other(
+                            "server sent a different part of the media than requested",
+                        ));
+                    }
+                }
+                sink.seek(SeekFrom::Start(0))?
+            }
+        };
+        self.report(written);
+
+        let mut buf = vec![0; CHUNK_SIZE];
+        loop {
+            let n = reader.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            sink.write_all(&buf[..n])?;
+            written += n as u64;
+            self.report(written);
+        }
+        sink.flush()?;
+
+        match self.size {
+            Some(size) if size != written => Err(Error::Other(
+                "downloaded length does not match the media size",
+            )),
+            _ => Ok(written),
+        }
+    }
+
+    /// Downloads the media to a file, resuming from the end of the file if it
+    /// already exists.
+    ///
+    /// Returns the total length of the media written to the file. See
+    /// [`write_to`] for the errors this method may return.
+    ///
+    /// [`write_to`]: #method.write_to
+    pub fn save<P: AsRef<Path>>(&mut self, path: P) -> Result<u64> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let written = self.write_to(&mut file)?;
+        file.set_len(written)?;
+        Ok(written)
+    }
+
+    fn report(&mut self, written: u64) {
+        if let Some(ref mut progress) = self.progress {
+            progress(written, self.size);
+        }
+    }
+}
+
+impl<'a> fmt::Debug for Download<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Download")
+            .field("url", &self.url)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use test_util::{http_response, serve};
+
+    fn download<'a>(cli: &'a Client, site: &str, size: Option<u64>) -> Download<'a> {
+        Download::new(cli, format!("{}/rest/download?id=1", site), size)
+    }
+
+    #[test]
+    fn resumes_from_end_of_sink() {
+        let (site, heads) = serve(vec![http_response(
+            "206 Partial Content",
+            &["Content-Range: bytes 4-9/10"],
+            b"efghij",
+        )]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+
+        let mut progress = Vec::new();
+        let mut sink = Cursor::new(b"abcd".to_vec());
+        let written = download(&cli, &site, Some(10))
+            .on_progress(|done, total| progress.push((done, total)))
+            .write_to(&mut sink)
+            .unwrap();
+
+        assert_eq!(written, 10);
+        assert_eq!(sink.into_inner(), b"abcdefghij");
+        assert!(heads
+            .recv()
+            .unwrap()
+            .to_lowercase()
+            .contains("range: bytes=4-"));
+        assert_eq!(progress.first(), Some(&(4, Some(10))));
+        assert_eq!(progress.last(), Some(&(10, Some(10))));
+    }
+
+    #[test]
+    fn restarts_when_range_is_ignored() {
+        let (site, _heads) = serve(vec![http_response("200 OK", &[], b"abcdefghij")]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+
+        let mut sink = Cursor::new(b"xyz".to_vec());
+        let written = download(&cli, &site, Some(10)).write_to(&mut sink).unwrap();
+
+        assert_eq!(written, 10);
+        assert_eq!(sink.into_inner(), b"abcdefghij");
+    }
+
+    #[test]
+    fn restarts_when_range_starts_elsewhere() {
+        let (site, heads) = serve(vec![
+            http_response(
+                "206 Partial Content",
+                &["Content-Range: bytes 2-9/10"],
+                b"cdefghij",
+            ),
+            http_response(
+                "206 Partial Content",
+                &["Content-Range: bytes 0-9/10"],
+                b"abcdefghij",
+            ),
+        ]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+
+        let mut sink = Cursor::new(b"abcd".to_vec());
+        let written = download(&cli, &site, Some(10)).write_to(&mut sink).unwrap();
+
+        assert_eq!(written, 10);
+        assert_eq!(sink.into_inner(), b"abcdefghij");
+        assert!(heads
+            .recv()
+            .unwrap()
+            .to_lowercase()
+            .contains("range: bytes=4-"));
+        assert!(heads
+            .recv()
+            .unwrap()
+            .to_lowercase()
+            .contains("range: bytes=0-"));
+    }
+
+    #[test]
+    fn partial_content_without_range_is_refetched() {
+        let (site, _heads) = serve(vec![
+            http_response("206 Partial Content", &[], b"efghij"),
+            http_response("200 OK", &[], b"abcdefghij"),
+        ]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+
+        let mut sink = Cursor::new(b"abcd".to_vec());
+        let written = download(&cli, &site, Some(10)).write_to(&mut sink).unwrap();
+
+        assert_eq!(written, 10);
+        assert_eq!(sink.into_inner(), b"abcdefghij");
+    }
+
+    #[test]
+    fn complete_sink_is_not_refetched() {
+        let cli = Client::new("http://127.0.0.1:1", "user", "pass").unwrap();
+        let mut sink = Cursor::new(b"abcdefghij".to_vec());
+        let written = download(&cli, "http://127.0.0.1:1", Some(10))
+            .write_to(&mut sink)
+            .unwrap();
+        assert_eq!(written, 10);
+    }
+
+    #[test]
+    fn size_mismatch_is_an_error() {
+        let (site, _heads) = serve(vec![http_response("200 OK", &[], b"abc")]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+
+        let mut sink = Cursor::new(Vec::new());
+        let res = download(&cli, &site, Some(10)).write_to(&mut sink);
+        match res {
+            Err(Error::Other(_)) => {}
+            other => panic!("expected a size mismatch, got {:?}", other),
+        }
+    }
+}
@@ -1,16 +1,28 @@
+use reqwest;
+use reqwest::header::{CONTENT_RANGE, CONTENT_TYPE};
+use reqwest::StatusCode;
 use serde::de::{Deserialize, Deserializer};
+use std::io::{self, Read};
 use std::ops::Index;
 use std::result;
 use std::str::FromStr;
 
+#[cfg(feature = "async")]
+use futures::{future, stream};
+use id::{MediaId, SongId, VideoId};
+use query::Query;
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture, SunkStream};
 use {Client, Error, Result};
 
+mod download;
 // pub mod format;
 pub mod podcast;
 mod radio;
 pub mod song;
 pub mod video;
 
+pub use self::download::Download;
 pub use self::radio::RadioStation;
 
 use self::song::Song;
@@ -31,6 +43,28 @@ pub trait Streamable {
     /// media without evaluating the stream itself.
     fn stream(&self, client: &Client) -> Result<Vec<u8>>;
 
+    /// Returns a reader over the raw bytes of the media.
+    ///
+    /// Unlike [`stream`](#tymethod.stream), the media is not buffered in
+    /// memory; bytes are read from the server as the reader is consumed. This
+    /// allows playback to begin as soon as the first bytes arrive.
+    ///
+    /// Supports the same transcoding options as `stream`.
+    fn stream_reader(&self, client: &Client) -> Result<MediaReader>;
+
+    /// Returns the raw bytes of the media without blocking.
+    ///
+    /// The asynchronous counterpart to [`stream`](#tymethod.stream).
+    #[cfg(feature = "async")]
+    fn stream_async(&self, client: &AsyncClient) -> SunkFuture<Vec<u8>>;
+
+    /// Returns a stream of chunks of the raw bytes of the media.
+    ///
+    /// The asynchronous counterpart to
+    /// [`stream_reader`](#tymethod.stream_reader).
+    #[cfg(feature = "async")]
+    fn stream_chunks_async(&self, client: &AsyncClient) -> SunkStream<Vec<u8>>;
+
     /// Returns a constructed URL for streaming.
     ///
     /// Supports transcoding options specified on the media beforehand. See the
@@ -47,9 +81,45 @@ pub trait Streamable {
     /// media without evaluating the stream itself.
     fn download(&self, client: &Client) -> Result<Vec<u8>>;
 
+    /// Returns a reader over the raw bytes of the media, without buffering
+    /// the media in memory.
+    fn download_reader(&self, client: &Client) -> Result<MediaReader>;
+
+    /// Returns the raw bytes of the media without blocking.
+    ///
+    /// The asynchronous counterpart to [`download`](#tymethod.download).
+    #[cfg(feature = "async")]
+    fn download_async(&self, client: &AsyncClient) -> SunkFuture<Vec<u8>>;
+
+    /// Returns a stream of chunks of the raw bytes of the media.
+    ///
+    /// The asynchronous counterpart to
+    /// [`download_reader`](#tymethod.download_reader).
+    #[cfg(feature = "async")]
+    fn download_chunks_async(&self, client: &AsyncClient) -> SunkStream<Vec<u8>>;
+
     /// Returns a constructed URL for downloading the song.
     fn download_url(&self, client: &Client) -> Result<String>;
 
+    /// Returns the size of the original media file in bytes, if known.
+    ///
+    /// This is the size of a [`download`](#tymethod.download); streams may
+    /// differ in size due to transcoding.
+    fn download_size(&self) -> Option<u64>;
+
+    /// Prepares a resumable download of the media.
+    ///
+    /// See the [`Download`] documentation for details.
+    ///
+    /// [`Download`]: ./struct.Download.html
+    fn resumable_download<'a>(&self, client: &'a Client) -> Result<Download<'a>> {
+        Ok(Download::new(
+            client,
+            self.download_url(client)?,
+            self.download_size(),
+        ))
+    }
+
     /// Returns the default encoding of the media.
     ///
     /// A Subsonic server is able to transcode media for streaming to reduce
@@ -112,6 +182,64 @@ pub trait Media {
     /// if the media does not have an associated cover art.
     fn cover_art<U: Into<Option<usize>>>(&self, client: &Client, size: U) -> Result<Vec<u8>>;
 
+    /// Returns a reader over the raw bytes of the cover art of the media,
+    /// without buffering the image in memory.
+    ///
+    /// # Errors
+    ///
+    /// Aside from errors that the `Client` may cause, the method will error
+    /// if the media does not have an associated cover art.
+    fn cover_art_reader<U: Into<Option<usize>>>(
+        &self,
+        client: &Client,
+        size: U,
+    ) -> Result<MediaReader> {
+        let cover = self
+            .cover_id()
+            .ok_or_else(|| Error::Other("no cover art found"))?;
+        let query = Query::with("id", cover).arg("size", size.into()).build();
+
+        client.get_reader("getCoverArt", query)
+    }
+
+    /// Returns the raw bytes of the cover art of the media without blocking.
+    ///
+    /// The asynchronous counterpart to [`cover_art`](#tymethod.cover_art).
+    #[cfg(feature = "async")]
+    fn cover_art_async<U: Into<Option<usize>>>(
+        &self,
+        client: &AsyncClient,
+        size: U,
+    ) -> SunkFuture<Vec<u8>> {
+        let cover = match self.cover_id() {
+            Some(cover) => cover,
+            None => return Box::new(future::err(Error::Other("no cover art found"))),
+        };
+        let query = Query::with("id", cover).arg("size", size.into()).build();
+
+        client.get_bytes("getCoverArt", query)
+    }
+
+    /// Returns a stream of chunks of the raw bytes of the cover art of the
+    /// media.
+    ///
+    /// The asynchronous counterpart to
+    /// [`cover_art_reader`](#method.cover_art_reader).
+    #[cfg(feature = "async")]
+    fn cover_art_chunks_async<U: Into<Option<usize>>>(
+        &self,
+        client: &AsyncClient,
+        size: U,
+    ) -> SunkStream<Vec<u8>> {
+        let cover = match self.cover_id() {
+            Some(cover) => cover,
+            None => return Box::new(stream::once(Err(Error::Other("no cover art found")))),
+        };
+        let query = Query::with("id", cover).arg("size", size.into()).build();
+
+        client.get_chunks("getCoverArt", query)
+    }
+
     /// Returns the URL pointing to the cover art of the media.
     ///
     /// # Errors
@@ -121,6 +249,108 @@ pub trait Media {
     fn cover_art_url<U: Into<Option<usize>>>(&self, client: &Client, size: U) -> Result<String>;
 }
 
+/// A reader over raw media being fetched from a Subsonic server.
+///
+/// Returned by the `*_reader` methods on [`Streamable`] and [`Media`]. Bytes
+/// are pulled from the connection as the reader is consumed, so memory use
+/// stays flat regardless of the size of the media, and an interrupted
+/// connection surfaces as an `io::Error` rather than a panic.
+///
+/// [`Streamable`]: ./trait.Streamable.html
+/// [`Media`]: ./trait.Media.html
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate sunk;
+/// use sunk::song::Song;
+/// use sunk::{Client, Streamable};
+///
+/// # fn run() -> sunk::Result<()> {
+/// # let site = "http://demo.subsonic.org";
+/// # let user = "guest3";
+/// # let password = "guest";
+/// use std::fs::File;
+/// use std::io;
+///
+/// let client = Client::new(site, user, password)?;
+/// let song = Song::get(&client, 1887)?;
+///
+/// let mut reader = song.download_reader(&client)?;
+/// let mut file = File::create("song.flac")?;
+/// io::copy(&mut reader, &mut file)?;
+/// # Ok(())
+/// # }
+/// # fn main() { }
+/// ```
+#[derive(Debug)]
+pub struct MediaReader {
+    inner: reqwest::Response,
+}
+
+impl MediaReader {
+    pub(crate) fn new(inner: reqwest::Response) -> MediaReader {
+        MediaReader { inner }
+    }
+
+    /// Returns the size of the media in bytes, if the server reported it.
+    ///
+    /// Servers typically won't report a length when transcoding.
+    pub fn content_length(&self) -> Option<u64> {
+        self.inner.content_length()
+    }
+
+    /// Returns `true` unless the body starts at the beginning of the media.
+    ///
+    /// See [`offset`](#method.offset) for where a partial body starts.
+    pub fn is_partial(&self) -> bool {
+        self.offset() != Some(0)
+    }
+
+    /// Returns the byte offset into the media that the body starts at.
+    ///
+    /// This is zero for a complete response, and the start of the
+    /// `Content-Range` the server sent for a partial one. A server may send a
+    /// different range than was asked for, so the offset should be checked
+    /// before appending the body to a partial file. `None` is returned if the
+    /// server sent part of the media without a readable `Content-Range`.
+    pub fn offset(&self) -> Option<u64> {
+        if self.inner.status() != StatusCode::PARTIAL_CONTENT {
+            return Some(0);
+        }
+        self.inner
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|range| range.to_str().ok())
+            .and_then(range_start)
+    }
+
+    /// Returns the MIME type of the media, if the server reported it.
+    pub fn content_type(&self) -> Option<&str> {
+        self.inner
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|ct| ct.to_str().ok())
+    }
+}
+
+/// Parses the first byte of a `Content-Range`, such as `bytes 4-9/10`.
+fn range_start(range: &str) -> Option<u64> {
+    let range = range.trim();
+    if !range.starts_with("bytes ") {
+        return None;
+    }
+    let range = range["bytes ".len()..].trim_start();
+    let end = range.find('-')?;
+    range[..end].parse().ok()
+}
+
+impl Read for MediaReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
 /// Information about currently playing media.
 ///
 /// Due to the "now playing" information possibly containing both audio and
@@ -137,7 +367,7 @@ pub struct NowPlaying {
     pub minutes_ago: usize,
     /// The ID of the player.
     pub player_id: usize,
-    id: usize,
+    id: MediaId,
     is_video: bool,
 }
 
@@ -154,7 +384,7 @@ impl NowPlaying {
         if self.is_video {
             Err(Error::Other("Now Playing info is not a song"))
         } else {
-            Song::get(client, self.id as u64)
+            Song::get(client, SongId::from(self.id.clone()))
         }
     }
 
@@ -170,7 +400,32 @@ impl NowPlaying {
         if !self.is_video {
             Err(Error::Other("Now Playing info is not a video"))
         } else {
-            Video::get(client, self.id)
+            Video::get(client, VideoId::from(self.id.clone()))
+        }
+    }
+
+    /// Fetches information about the currently playing song without blocking.
+    ///
+    /// The asynchronous counterpart to [`song_info`](#method.song_info).
+    #[cfg(feature = "async")]
+    pub fn song_info_async(&self, client: &AsyncClient) -> SunkFuture<Song> {
+        if self.is_video {
+            Box::new(future::err(Error::Other("Now Playing info is not a song")))
+        } else {
+            Song::get_async(client, SongId::from(self.id.clone()))
+        }
+    }
+
+    /// Fetches information about the currently playing video without
+    /// blocking.
+    ///
+    /// The asynchronous counterpart to [`video_info`](#method.video_info).
+    #[cfg(feature = "async")]
+    pub fn video_info_async(&self, client: &AsyncClient) -> SunkFuture<Video> {
+        if !self.is_video {
+            Box::new(future::err(Error::Other("Now Playing info is not a video")))
+        } else {
+            Video::get_async(client, VideoId::from(self.id.clone()))
         }
     }
 
@@ -226,6 +481,27 @@ impl Hls {
     pub fn get_bytes(&self, client: &Client) -> Result<Vec<u8>> {
         client.hls_bytes(self)
     }
+
+    /// Returns a reader over the bytes of the slice, without buffering the
+    /// slice in memory.
+    pub fn reader(&self, client: &Client) -> Result<MediaReader> {
+        client.hls_reader(self)
+    }
+
+    /// Fetches the raw bytes of the slice from the `AsyncClient` without
+    /// blocking.
+    #[cfg(feature = "async")]
+    pub fn get_bytes_async(&self, client: &AsyncClient) -> SunkFuture<Vec<u8>> {
+        client.hls_bytes(self)
+    }
+
+    /// Returns a stream of chunks of the bytes of the slice.
+    ///
+    /// The asynchronous counterpart to [`reader`](#method.reader).
+    #[cfg(feature = "async")]
+    pub fn chunks_async(&self, client: &AsyncClient) -> SunkStream<Vec<u8>> {
+        client.hls_chunks(self)
+    }
 }
 
 impl FromStr for HlsPlaylist {
@@ -298,7 +574,7 @@ impl<'de> Deserialize<'de> for NowPlaying {
             username: String,
             minutes_ago: usize,
             player_id: usize,
-            id: String,
+            id: MediaId,
             is_dir: bool,
             title: String,
             size: usize,
@@ -319,7 +595,7 @@ impl<'de> Deserialize<'de> for NowPlaying {
             user: raw.username,
             minutes_ago: raw.minutes_ago,
             player_id: raw.player_id,
-            id: raw.id.parse().unwrap(),
+            id: raw.id,
             is_video: raw.is_video,
         })
     }
@@ -328,6 +604,68 @@ impl<'de> Deserialize<'de> for NowPlaying {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_util;
+
+    #[test]
+    fn parse_content_range() {
+        assert_eq!(range_start("bytes 4-9/10"), Some(4));
+        assert_eq!(range_start("bytes 0-9/*"), Some(0));
+        assert_eq!(range_start("bytes */10"), None);
+        assert_eq!(range_start("items 4-9/10"), None);
+        assert_eq!(range_start("bytes x-9/10"), None);
+    }
+
+    #[test]
+    fn hls_reader_reads_body() {
+        let body = (0..64 * 1024).map(|n| n as u8).collect::<Vec<_>>();
+        let (site, requests) = test_util::serve(vec![test_util::http_response(
+            "200 OK",
+            &["Content-Type: video/MP2T"],
+            &body,
+        )]);
+        let cli = Client::new(&site, "guest3", "guest").unwrap();
+        let slice = Hls {
+            inc: 10,
+            url: "/ext/stream/stream.ts?id=1887&hls=true&timeOffset=0".into(),
+        };
+
+        let mut reader = slice.reader(&cli).unwrap();
+        assert_eq!(reader.content_length(), Some(body.len() as u64));
+        assert_eq!(reader.content_type(), Some("video/MP2T"));
+
+        let mut read = Vec::new();
+        reader.read_to_end(&mut read).unwrap();
+        assert_eq!(read, body);
+        assert!(requests
+            .recv()
+            .unwrap()
+            .starts_with("GET /ext/stream/stream.ts?id=1887"));
+    }
+
+    #[test]
+    fn hls_reader_surfaces_api_error() {
+        let body = br#"{"subsonic-response": {
+            "status": "failed",
+            "version": "1.14.0",
+            "error": { "code": 70, "message": "Requested resource not found" }
+        }}"#;
+        let (site, _) = test_util::serve(vec![test_util::http_response(
+            "200 OK",
+            &["Content-Type: application/json"],
+            body,
+        )]);
+        let cli = Client::new(&site, "guest3", "guest").unwrap();
+        let slice = Hls {
+            inc: 10,
+            url: "/ext/stream/stream.ts?id=1887&hls=true&timeOffset=0".into(),
+        };
+
+        match slice.get_bytes(&cli) {
+            Err(Error::Api(::ApiError::NotFound)) => (),
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("an error response should not be returned as media"),
+        }
+    }
 
     #[test]
     fn parse_hls() {
@@ -1,63 +1,138 @@
 use serde::de::{Deserialize, Deserializer};
-use std::result;
+use serde_json;
+use std::{fmt, result};
 
+#[cfg(feature = "async")]
+use futures::{future, Future};
+use id::{EpisodeId, MediaId, PodcastId};
 use query::Query;
-use {Client, Result};
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture, SunkStream};
+use {Client, Error, Media, MediaReader, Result, Streamable};
 
-#[derive(Debug)]
+/// A podcast channel the server subscribes to.
+#[derive(Debug, Clone)]
 pub struct Podcast {
-    id: usize,
-    url: String,
-    title: String,
-    description: String,
-    cover_art: String,
-    image_url: String,
-    status: String,
-    episodes: Vec<Episode>,
-    error: Option<String>,
+    /// The ID of the channel.
+    pub id: PodcastId,
+    /// The URL of the channel's feed.
+    pub url: String,
+    /// The title of the channel.
+    pub title: String,
+    /// The description of the channel.
+    pub description: String,
+    /// The URL of the channel's artwork, as given by the feed.
+    pub image_url: Option<String>,
+    /// The status of the channel.
+    pub status: PodcastStatus,
+    /// The error encountered when refreshing the channel, if any.
+    pub error: Option<String>,
+    /// The episodes of the channel.
+    ///
+    /// This will be empty if the channel was listed without its episodes.
+    pub episodes: Vec<Episode>,
 }
 
-#[derive(Debug)]
+/// An episode of a podcast channel.
+#[derive(Debug, Clone)]
 pub struct Episode {
-    id: usize,
-    parent: usize,
-    is_dir: bool,
-    title: String,
-    album: String,
-    artist: String,
-    year: usize,
-    cover_art: String,
-    size: usize,
-    content_type: String,
-    suffix: String,
-    duration: usize,
-    bitrate: usize,
-    is_video: bool,
-    created: String,
-    artist_id: String,
-    media_type: String,
-    stream_id: String,
-    channel_id: String,
-    description: String,
-    status: String,
-    publish_date: String,
+    /// The ID of the episode.
+    pub id: EpisodeId,
+    /// The ID of the channel the episode belongs to. Left out for episodes
+    /// found through a [`Bookmark`].
+    ///
+    /// [`Bookmark`]: ../struct.Bookmark.html
+    pub channel_id: Option<PodcastId>,
+    /// The ID used to stream the episode, once it has been downloaded by the
+    /// server.
+    pub stream_id: Option<MediaId>,
+    /// The title of the episode.
+    pub title: String,
+    /// The description of the episode.
+    pub description: String,
+    /// The status of the episode on the server. Left out for episodes found
+    /// through a [`Bookmark`].
+    ///
+    /// [`Bookmark`]: ../struct.Bookmark.html
+    pub status: Option<PodcastStatus>,
+    /// When the episode was published.
+    pub publish_date: Option<String>,
+    /// The album the episode is tagged with, usually the channel title.
+    pub album: Option<String>,
+    /// The artist the episode is tagged with.
+    pub artist: Option<String>,
+    /// The year the episode was released.
+    pub year: Option<usize>,
+    /// The size of the episode in bytes, once downloaded.
+    pub size: Option<usize>,
+    /// The length of the episode in seconds, once downloaded.
+    pub duration: Option<usize>,
+    /// The bit rate of the episode, once downloaded.
+    pub bitrate: Option<usize>,
+    cover_id: Option<String>,
+    content_type: Option<String>,
+    transcoded_content_type: Option<String>,
+    stream_br: Option<usize>,
+    stream_tc: Option<String>,
+}
+
+/// The status of a podcast channel or episode on the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PodcastStatus {
+    /// Not yet downloaded.
+    New,
+    /// Currently being downloaded.
+    Downloading,
+    /// Downloaded and available to play.
+    Completed,
+    /// Downloading failed.
+    Error,
+    /// Deleted from the server.
+    Deleted,
+    /// Skipped by the server when downloading.
+    Skipped,
+    /// A status not known to `sunk`.
+    #[serde(other)]
+    Unknown,
+}
+
+impl fmt::Display for PodcastStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::PodcastStatus::*;
+        let fmt = match *self {
+            New => "new",
+            Downloading => "downloading",
+            Completed => "completed",
+            Error => "error",
+            Deleted => "deleted",
+            Skipped => "skipped",
+            Unknown => "unknown",
+        };
+        write!(f, "{}", fmt)
+    }
 }
 
 impl Podcast {
     /// Fetches the details of a single podcast and its episodes.
-    pub fn get<U>(client: &Client, id: U) -> Result<Podcast>
+    ///
+    /// # Errors
+    ///
+    /// Aside from errors the `Client` may cause, the method will error if
+    /// there is no podcast matching the provided ID.
+    pub fn get<I>(client: &Client, id: I) -> Result<Podcast>
     where
-        U: Into<Option<usize>>,
+        I: Into<PodcastId>,
     {
         let channel = client.get("getPodcasts", Query::with("id", id.into()))?;
-        Ok(get_list_as!(channel, Podcast).remove(0))
+        first_podcast(channel)
     }
+
     /// Returns a list of all podcasts the server subscribes to and,
     /// optionally, their episodes.
-    pub fn list<B, U>(client: &Client, include_episodes: B) -> Result<Vec<Podcast>>
+    pub fn list<B>(client: &Client, include_episodes: B) -> Result<Vec<Podcast>>
     where
         B: Into<Option<bool>>,
-        U: Into<Option<usize>>,
     {
         let channel = client.get(
             "getPodcasts",
@@ -65,6 +140,107 @@ impl Podcast {
         )?;
         Ok(get_list_as!(channel, Podcast))
     }
+
+    /// Subscribes the server to the podcast feed at the given URL.
+    ///
+    /// # Errors
+    ///
+    /// The user must have the `podcast_role` permission to manage podcasts.
+    pub fn create(client: &Client, url: &str) -> Result<()> {
+        client.get("createPodcastChannel", Query::with("url", url))?;
+        Ok(())
+    }
+
+    /// Unsubscribes the server from the podcast, deleting its episodes.
+    ///
+    /// # Errors
+    ///
+    /// The user must have the `podcast_role` permission to manage podcasts.
+    pub fn delete(&self, client: &Client) -> Result<()> {
+        client.get("deletePodcastChannel", Query::with("id", &self.id))?;
+        Ok(())
+    }
+
+    /// Asks the server to check all podcasts for new episodes.
+    ///
+    /// # Errors
+    ///
+    /// The user must have the `podcast_role` permission to manage podcasts.
+    pub fn refresh(client: &Client) -> Result<()> {
+        client.get("refreshPodcasts", Query::none())?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl Podcast {
+    /// Fetches the details of a single podcast and its episodes without
+    /// blocking.
+    ///
+    /// The asynchronous counterpart to [`get`](#method.get).
+    pub fn get_async<I>(client: &AsyncClient, id: I) -> SunkFuture<Podcast>
+    where
+        I: Into<PodcastId>,
+    {
+        Box::new(
+            client
+                .get("getPodcasts", Query::with("id", id.into()))
+                .and_then(first_podcast),
+        )
+    }
+
+    /// Returns a list of all podcasts the server subscribes to without
+    /// blocking.
+    ///
+    /// The asynchronous counterpart to [`list`](#method.list).
+    pub fn list_async<B>(client: &AsyncClient, include_episodes: B) -> SunkFuture<Vec<Podcast>>
+    where
+        B: Into<Option<bool>>,
+    {
+        let args = Query::with("includeEpisodes", include_episodes.into());
+        Box::new(
+            client
+                .get("getPodcasts", args)
+                .and_then(|channel| -> Result<_> { Ok(get_list_as!(channel, Podcast)) }),
+        )
+    }
+
+    /// Subscribes the server to a podcast feed without blocking.
+    ///
+    /// The asynchronous counterpart to [`create`](#method.create).
+    pub fn create_async(client: &AsyncClient, url: &str) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get("createPodcastChannel", Query::with("url", url))
+                .map(|_| ()),
+        )
+    }
+
+    /// Unsubscribes the server from the podcast without blocking.
+    ///
+    /// The asynchronous counterpart to [`delete`](#method.delete).
+    pub fn delete_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get("deletePodcastChannel", Query::with("id", &self.id))
+                .map(|_| ()),
+        )
+    }
+
+    /// Asks the server to check all podcasts for new episodes without
+    /// blocking.
+    ///
+    /// The asynchronous counterpart to [`refresh`](#method.refresh).
+    pub fn refresh_async(client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(client.get("refreshPodcasts", Query::none()).map(|_| ()))
+    }
+}
+
+fn first_podcast(channel: serde_json::Value) -> Result<Podcast> {
+    get_list_as!(channel, Podcast)
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::Other("no podcast found"))
 }
 
 impl Episode {
@@ -77,6 +253,200 @@ impl Episode {
         let episode = client.get("getNewestPodcasts", Query::with("count", count.into()))?;
         Ok(get_list_as!(episode, Episode))
     }
+
+    /// Asks the server to download the episode, making it available to
+    /// stream.
+    ///
+    /// # Errors
+    ///
+    /// The user must have the `podcast_role` permission to manage podcasts.
+    pub fn queue_download(&self, client: &Client) -> Result<()> {
+        client.get("downloadPodcastEpisode", Query::with("id", &self.id))?;
+        Ok(())
+    }
+
+    /// Deletes the episode from the server.
+    ///
+    /// # Errors
+    ///
+    /// The user must have the `podcast_role` permission to manage podcasts.
+    pub fn delete(&self, client: &Client) -> Result<()> {
+        client.get("deletePodcastEpisode", Query::with("id", &self.id))?;
+        Ok(())
+    }
+
+    /// Returns `true` if the server has downloaded the episode.
+    pub fn is_downloaded(&self) -> bool {
+        self.status == Some(PodcastStatus::Completed)
+    }
+}
+
+#[cfg(feature = "async")]
+impl Episode {
+    /// Returns a list of the newest episodes of podcasts the server subscribes
+    /// to without blocking.
+    ///
+    /// The asynchronous counterpart to [`newest`](#method.newest).
+    pub fn newest_async<U>(client: &AsyncClient, count: U) -> SunkFuture<Vec<Episode>>
+    where
+        U: Into<Option<usize>>,
+    {
+        Box::new(
+            client
+                .get("getNewestPodcasts", Query::with("count", count.into()))
+                .and_then(|episode| -> Result<_> { Ok(get_list_as!(episode, Episode)) }),
+        )
+    }
+
+    /// Asks the server to download the episode without blocking.
+    ///
+    /// The asynchronous counterpart to [`queue_download`](#method.queue_download).
+    pub fn queue_download_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get("downloadPodcastEpisode", Query::with("id", &self.id))
+                .map(|_| ()),
+        )
+    }
+
+    /// Deletes the episode from the server without blocking.
+    ///
+    /// The asynchronous counterpart to [`delete`](#method.delete).
+    pub fn delete_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get("deletePodcastEpisode", Query::with("id", &self.id))
+                .map(|_| ()),
+        )
+    }
+}
+
+impl Episode {
+    /// Returns the ID of the downloaded media, or an error if the server has
+    /// not downloaded the episode.
+    ///
+    /// Episodes without a status are taken to be downloaded if they have
+    /// media to stream.
+    fn media_id(&self) -> Result<&MediaId> {
+        match self.stream_id {
+            Some(ref id) if self.status.is_none() || self.is_downloaded() => Ok(id),
+            _ => Err(Error::Other(
+                "episode has not been downloaded by the server",
+            )),
+        }
+    }
+
+    fn stream_query(&self) -> Result<Query> {
+        Ok(Query::with("id", self.media_id()?)
+            .arg("maxBitRate", self.stream_br)
+            .arg("format", self.stream_tc.as_deref())
+            .build())
+    }
+
+    fn download_query(&self) -> Result<Query> {
+        Ok(Query::with("id", self.media_id()?))
+    }
+}
+
+impl Streamable for Episode {
+    fn stream(&self, client: &Client) -> Result<Vec<u8>> {
+        client.get_bytes("stream", self.stream_query()?)
+    }
+
+    fn stream_reader(&self, client: &Client) -> Result<MediaReader> {
+        client.get_reader("stream", self.stream_query()?)
+    }
+
+    #[cfg(feature = "async")]
+    fn stream_async(&self, client: &AsyncClient) -> SunkFuture<Vec<u8>> {
+        match self.stream_query() {
+            Ok(q) => client.get_bytes("stream", q),
+            Err(e) => Box::new(future::err(e)),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn stream_chunks_async(&self, client: &AsyncClient) -> SunkStream<Vec<u8>> {
+        match self.stream_query() {
+            Ok(q) => client.get_chunks("stream", q),
+            Err(e) => Box::new(future::err(e).into_stream()),
+        }
+    }
+
+    fn stream_url(&self, client: &Client) -> Result<String> {
+        client.build_url("stream", self.stream_query()?)
+    }
+
+    fn download(&self, client: &Client) -> Result<Vec<u8>> {
+        client.get_bytes("download", self.download_query()?)
+    }
+
+    fn download_reader(&self, client: &Client) -> Result<MediaReader> {
+        client.get_reader("download", self.download_query()?)
+    }
+
+    #[cfg(feature = "async")]
+    fn download_async(&self, client: &AsyncClient) -> SunkFuture<Vec<u8>> {
+        match self.download_query() {
+            Ok(q) => client.get_bytes("download", q),
+            Err(e) => Box::new(future::err(e)),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn download_chunks_async(&self, client: &AsyncClient) -> SunkStream<Vec<u8>> {
+        match self.download_query() {
+            Ok(q) => client.get_chunks("download", q),
+            Err(e) => Box::new(future::err(e).into_stream()),
+        }
+    }
+
+    fn download_url(&self, client: &Client) -> Result<String> {
+        client.build_url("download", self.download_query()?)
+    }
+
+    fn download_size(&self) -> Option<u64> {
+        self.size.map(|s| s as u64)
+    }
+
+    fn encoding(&self) -> &str {
+        self.transcoded_content_type
+            .as_deref()
+            .or(self.content_type.as_deref())
+            .unwrap_or("")
+    }
+
+    fn set_max_bit_rate(&mut self, bit_rate: usize) {
+        self.stream_br = Some(bit_rate);
+    }
+
+    fn set_transcoding(&mut self, format: &str) {
+        self.stream_tc = Some(format.to_string());
+    }
+}
+
+impl Media for Episode {
+    fn has_cover_art(&self) -> bool {
+        self.cover_id.is_some()
+    }
+
+    fn cover_id(&self) -> Option<&str> {
+        self.cover_id.as_deref()
+    }
+
+    fn cover_art<U: Into<Option<usize>>>(&self, client: &Client, size: U) -> Result<Vec<u8>> {
+        let cover = self.cover_id().ok_or(Error::Other("no cover art found"))?;
+        let query = Query::with("id", cover).arg("size", size.into()).build();
+
+        client.get_bytes("getCoverArt", query)
+    }
+
+    fn cover_art_url<U: Into<Option<usize>>>(&self, client: &Client, size: U) -> Result<String> {
+        let cover = self.cover_id().ok_or(Error::Other("no cover art found"))?;
+        let query = Query::with("id", cover).arg("size", size.into()).build();
+
+        client.build_url("getCoverArt", query)
+    }
 }
 
 impl<'de> Deserialize<'de> for Podcast {
@@ -87,13 +457,15 @@ impl<'de> Deserialize<'de> for Podcast {
         #[derive(Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct _Podcast {
-            id: String,
+            id: PodcastId,
             url: String,
+            #[serde(default)]
             title: String,
+            #[serde(default)]
             description: String,
-            cover_art: String,
-            image_url: String,
-            status: String,
+            original_image_url: Option<String>,
+            image_url: Option<String>,
+            status: PodcastStatus,
             #[serde(default)]
             episode: Vec<Episode>,
             #[serde(default)]
@@ -103,12 +475,11 @@ impl<'de> Deserialize<'de> for Podcast {
         let raw = _Podcast::deserialize(de)?;
 
         Ok(Podcast {
-            id: raw.id.parse().unwrap(),
+            id: raw.id,
             url: raw.url,
             title: raw.title,
             description: raw.description,
-            cover_art: raw.cover_art,
-            image_url: raw.image_url,
+            image_url: raw.original_image_url.or(raw.image_url),
             status: raw.status,
             episodes: raw.episode,
             error: if raw.error_message.is_empty() {
@@ -128,56 +499,197 @@ impl<'de> Deserialize<'de> for Episode {
         #[derive(Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct _Episode {
-            id: String,
-            parent: String,
-            is_dir: bool,
+            id: EpisodeId,
             title: String,
-            album: String,
-            artist: String,
-            year: usize,
-            cover_art: String,
-            size: usize,
-            content_type: String,
-            suffix: String,
-            duration: usize,
-            bit_rate: usize,
-            is_video: bool,
-            created: String,
-            artist_id: String,
-            #[serde(rename = "type")]
-            _type: String,
-            stream_id: String,
-            channel_id: String,
+            album: Option<String>,
+            artist: Option<String>,
+            year: Option<usize>,
+            cover_art: Option<String>,
+            size: Option<usize>,
+            content_type: Option<String>,
+            transcoded_content_type: Option<String>,
+            duration: Option<usize>,
+            bit_rate: Option<usize>,
+            stream_id: Option<MediaId>,
+            channel_id: Option<PodcastId>,
+            #[serde(default)]
             description: String,
-            status: String,
-            publish_date: String,
+            status: Option<PodcastStatus>,
+            publish_date: Option<String>,
         }
 
         let raw = _Episode::deserialize(de)?;
 
         Ok(Episode {
-            id: raw.id.parse().unwrap(),
-            parent: raw.parent.parse().unwrap(),
-            is_dir: raw.is_dir,
+            id: raw.id,
+            channel_id: raw.channel_id,
+            stream_id: raw.stream_id,
             title: raw.title,
+            description: raw.description,
+            status: raw.status,
+            publish_date: raw.publish_date,
             album: raw.album,
             artist: raw.artist,
             year: raw.year,
-            cover_art: raw.cover_art,
             size: raw.size,
-            content_type: raw.content_type,
-            suffix: raw.suffix,
             duration: raw.duration,
             bitrate: raw.bit_rate,
-            is_video: raw.is_video,
-            created: raw.created,
-            artist_id: raw.artist_id,
-            media_type: raw._type,
-            stream_id: raw.stream_id,
-            channel_id: raw.channel_id,
-            description: raw.description,
-            status: raw.status,
-            publish_date: raw.publish_date,
+            cover_id: raw.cover_art,
+            content_type: raw.content_type,
+            transcoded_content_type: raw.transcoded_content_type,
+            stream_br: None,
+            stream_tc: None,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util;
+
+    #[test]
+    fn parse_podcast() {
+        let parsed = serde_json::from_value::<Podcast>(raw()).unwrap();
+        assert_eq!(parsed.id, "1");
+        assert_eq!(parsed.title, "Mark Kermode and Simon Mayo's Film Reviews");
+        assert_eq!(parsed.status, PodcastStatus::Completed);
+        assert_eq!(parsed.error, None);
+        assert_eq!(parsed.episodes.len(), 2);
+    }
+
+    #[test]
+    fn parse_episodes() {
+        let parsed = serde_json::from_value::<Podcast>(raw()).unwrap();
+        let done = &parsed.episodes[0];
+        assert!(done.is_downloaded());
+        assert_eq!(done.stream_id, Some(MediaId::from(523)));
+        assert_eq!(done.channel_id, Some("1".into()));
+        assert_eq!(done.size, Some(78421341));
+
+        // Episodes that haven't been downloaded carry no media details.
+        let new = &parsed.episodes[1];
+        assert_eq!(new.status, Some(PodcastStatus::New));
+        assert_eq!(new.stream_id, None);
+        assert_eq!(new.size, None);
+    }
+
+    #[test]
+    fn unknown_status() {
+        let status = serde_json::from_str::<PodcastStatus>(r#""paused""#).unwrap();
+        assert_eq!(status, PodcastStatus::Unknown);
+    }
+
+    #[test]
+    fn manage_podcasts() {
+        let (site, heads) = test_util::serve(vec![
+            test_util::ok_response(""),
+            test_util::ok_response(""),
+            test_util::ok_response(""),
+            test_util::ok_response(""),
+            test_util::ok_response(""),
+        ]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+        let podcast = serde_json::from_value::<Podcast>(raw()).unwrap();
+        let head = || heads.recv().unwrap();
+
+        Podcast::create(&cli, "http://example.com/feed.xml?a=1&b=2").unwrap();
+        let create = head();
+        assert!(create.contains("/rest/createPodcastChannel?"));
+        assert!(create.contains("&url=http%3A%2F%2Fexample.com%2Ffeed.xml%3Fa%3D1%26b%3D2 "));
+        Podcast::refresh(&cli).unwrap();
+        assert!(head().contains("/rest/refreshPodcasts?"));
+        podcast.episodes[1].queue_download(&cli).unwrap();
+        assert!(head().contains("/rest/downloadPodcastEpisode?"));
+        podcast.episodes[0].delete(&cli).unwrap();
+        assert!(head().contains("/rest/deletePodcastEpisode?"));
+        podcast.delete(&cli).unwrap();
+        assert!(head().contains("/rest/deletePodcastChannel?"));
+    }
+
+    #[test]
+    fn stream_downloaded_episode() {
+        let cli = Client::new("http://localhost", "user", "pass").unwrap();
+        let mut episode = serde_json::from_value::<Podcast>(raw())
+            .unwrap()
+            .episodes
+            .remove(0);
+        episode.set_max_bit_rate(96);
+        episode.set_transcoding("ogg");
+
+        let url = episode.stream_url(&cli).unwrap();
+        assert!(url.ends_with("&id=523&maxBitRate=96&format=ogg"));
+        assert!(episode.download_url(&cli).unwrap().ends_with("&id=523"));
+        assert_eq!(episode.download_size(), Some(78421341));
+        assert_eq!(episode.encoding(), "audio/mpeg");
+        assert_eq!(episode.cover_id(), Some("24"));
+    }
+
+    #[test]
+    fn refuse_undownloaded_episode() {
+        let cli = Client::new("http://localhost", "user", "pass").unwrap();
+        let episode = serde_json::from_value::<Podcast>(raw())
+            .unwrap()
+            .episodes
+            .remove(1);
+
+        match episode.stream_url(&cli) {
+            Err(Error::Other(msg)) => assert!(msg.contains("not been downloaded")),
+            other => panic!("expected the episode to be refused, got {:?}", other),
+        }
+        assert!(episode.download(&cli).is_err());
+        assert!(episode.resumable_download(&cli).is_err());
+    }
+
+    #[test]
+    fn get_missing_podcast() {
+        let (site, _heads) = test_util::serve(vec![test_util::ok_response(r#""podcasts": {}"#)]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+        assert!(Podcast::get(&cli, 4).is_err());
+    }
+
+    fn raw() -> serde_json::Value {
+        serde_json::from_str(
+            r#"{
+            "id" : "1",
+            "url" : "http://downloads.bbc.co.uk/podcasts/fivelive/kermode/rss.xml",
+            "title" : "Mark Kermode and Simon Mayo's Film Reviews",
+            "description" : "Film reviews from the BBC.",
+            "coverArt" : "pod-1",
+            "originalImageUrl" : "http://www.bbc.co.uk/kermode.jpg",
+            "status" : "completed",
+            "episode" : [ {
+                "id" : "34",
+                "streamId" : "523",
+                "channelId" : "1",
+                "title" : "Jan 26: Blade Runner 2049",
+                "description" : "Mark and Simon review the week's films.",
+                "publishDate" : "2018-01-26T16:30:00.000Z",
+                "status" : "completed",
+                "parent" : "11",
+                "isDir" : false,
+                "year" : 2018,
+                "genre" : "Podcast",
+                "coverArt" : "24",
+                "size" : 78421341,
+                "contentType" : "audio/mpeg",
+                "suffix" : "mp3",
+                "duration" : 3146,
+                "bitRate" : 128,
+                "isVideo" : false,
+                "created" : "2018-01-27T10:00:00.000Z",
+                "artistId" : "453",
+                "type" : "podcast"
+            }, {
+                "id" : "35",
+                "channelId" : "1",
+                "title" : "Feb 2: The Post",
+                "description" : "Mark and Simon review the week's films.",
+                "publishDate" : "2018-02-02T16:30:00.000Z",
+                "status" : "new"
+            } ]
+        }"#,
+        )
+        .unwrap()
+    }
+}
@@ -1,12 +1,17 @@
 use serde::de::{Deserialize, Deserializer};
 use std::result;
 
+#[cfg(feature = "async")]
+use futures::Future;
+use id::RadioStationId;
 use query::Query;
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture};
 use {Client, Result};
 
 #[derive(Debug)]
 pub struct RadioStation {
-    id: usize,
+    id: RadioStationId,
     pub name: String,
     pub stream_url: String,
     pub homepage_url: Option<String>,
@@ -20,14 +25,14 @@ impl<'de> Deserialize<'de> for RadioStation {
         #[derive(Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct _Station {
-            id: String,
+            id: RadioStationId,
             name: String,
             stream_url: String,
             homepage_url: Option<String>,
         }
         let raw = _Station::deserialize(de)?;
         Ok(RadioStation {
-            id: raw.id.parse().unwrap(),
+            id: raw.id,
             name: raw.name,
             stream_url: raw.stream_url,
             homepage_url: raw.homepage_url,
@@ -36,8 +41,8 @@ impl<'de> Deserialize<'de> for RadioStation {
 }
 
 impl RadioStation {
-    pub fn id(&self) -> usize {
-        self.id
+    pub fn id(&self) -> &RadioStationId {
+        &self.id
     }
 
     pub fn list(client: &Client) -> Result<Vec<RadioStation>> {
@@ -47,29 +52,81 @@ impl RadioStation {
     }
 
     pub fn create(client: &Client, name: &str, url: &str, homepage: Option<&str>) -> Result<()> {
-        let args = Query::with("name", name)
-            .arg("streamUrl", url)
-            .arg("homepageUrl", homepage)
-            .build();
+        let args = create_query(name, url, homepage);
         client.get("createInternetRadioStation", args)?;
         Ok(())
     }
 
     pub fn update(&self, client: &Client) -> Result<()> {
-        let args = Query::with("id", self.id)
+        client.get("updateInternetRadioStation", self.update_query())?;
+        Ok(())
+    }
+
+    pub fn delete(&self, client: &Client) -> Result<()> {
+        client.get("deleteInternetRadioStation", Query::with("id", &self.id))?;
+        Ok(())
+    }
+
+    fn update_query(&self) -> Query {
+        Query::with("id", &self.id)
             .arg("streamUrl", self.stream_url.as_str())
             .arg("name", self.name.as_str())
             .arg(
                 "homepageUrl",
                 self.homepage_url.as_ref().map(|s| s.as_str()),
             )
-            .build();
-        client.get("updateInternetRadioStation", args)?;
-        Ok(())
+            .build()
     }
+}
 
-    pub fn delete(&self, client: &Client) -> Result<()> {
-        client.get("deleteInternetRadioStation", Query::with("id", self.id))?;
-        Ok(())
+#[cfg(feature = "async")]
+impl RadioStation {
+    /// Lists all internet radio stations without blocking.
+    pub fn list_async(client: &AsyncClient) -> SunkFuture<Vec<RadioStation>> {
+        Box::new(
+            client
+                .get("getInternetRadioStations", Query::none())
+                .and_then(|res| -> Result<_> {
+                    #[allow(non_snake_case)]
+                    let internetRadioStation = res;
+                    Ok(get_list_as!(internetRadioStation, RadioStation))
+                }),
+        )
+    }
+
+    /// Creates an internet radio station without blocking.
+    pub fn create_async(
+        client: &AsyncClient,
+        name: &str,
+        url: &str,
+        homepage: Option<&str>,
+    ) -> SunkFuture<()> {
+        let args = create_query(name, url, homepage);
+        Box::new(client.get("createInternetRadioStation", args).map(|_| ()))
+    }
+
+    /// Pushes any changes made to the station to the server without blocking.
+    pub fn update_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get("updateInternetRadioStation", self.update_query())
+                .map(|_| ()),
+        )
+    }
+
+    /// Removes the station from the server without blocking.
+    pub fn delete_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get("deleteInternetRadioStation", Query::with("id", &self.id))
+                .map(|_| ()),
+        )
     }
 }
+
+fn create_query(name: &str, url: &str, homepage: Option<&str>) -> Query {
+    Query::with("name", name)
+        .arg("streamUrl", url)
+        .arg("homepageUrl", homepage)
+        .build()
+}
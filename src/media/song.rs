@@ -3,26 +3,31 @@ use serde_json;
 use std::fmt;
 use std::ops::Range;
 
+#[cfg(feature = "async")]
+use futures::Future;
+use id::{AlbumId, ArtistId, MusicFolderId, SongId};
 use query::Query;
-use search::SearchPage;
-use {Client, Error, HlsPlaylist, Media, Result, Streamable};
+use search::{self, Pages, SearchPage};
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture, SunkStream};
+use {Bookmark, Client, Error, HlsPlaylist, Media, MediaReader, Result, Streamable};
 
 /// A work of music contained on a Subsonic server.
 #[derive(Debug, Clone)]
 pub struct Song {
     /// Unique identifier for the song.
-    pub id: u64,
+    pub id: SongId,
     /// Title of the song. Prefers the song's ID3 tags, but will fall back to
     /// the file name.
     pub title: String,
     /// Album the song belongs to. Reads from the song's ID3 tags.
     pub album: Option<String>,
     /// The ID of the released album.
-    album_id: Option<u64>,
+    album_id: Option<AlbumId>,
     /// Credited artist for the song. Reads from the song's ID3 tags.
     pub artist: Option<String>,
     /// The ID of the releasing artist.
-    artist_id: Option<u64>,
+    artist_id: Option<ArtistId>,
     /// Position of the song in the album.
     pub track: Option<u64>,
     /// Year the song was released.
@@ -43,6 +48,8 @@ pub struct Song {
     transcoded_suffix: Option<String>,
     /// Duration of the song, in seconds.
     pub duration: Option<u64>,
+    /// When the user starred the song, if they have.
+    pub starred: Option<String>,
     /// The absolute path of the song in the server database.
     path: String,
     /// Will always be "song".
@@ -60,8 +67,11 @@ impl Song {
     ///
     /// Aside from other errors the `Client` may cause, the server will return
     /// an error if there is no song matching the provided ID.
-    pub fn get(client: &Client, id: u64) -> Result<Song> {
-        let res = client.get("getSong", Query::with("id", id))?;
+    pub fn get<I>(client: &Client, id: I) -> Result<Song>
+    where
+        I: Into<SongId>,
+    {
+        let res = client.get("getSong", Query::with("id", id.into()))?;
         Ok(serde_json::from_value(res)?)
     }
 
@@ -74,7 +84,7 @@ impl Song {
     where
         U: Into<Option<usize>>,
     {
-        let args = Query::with("id", self.id)
+        let args = Query::with("id", &self.id)
             .arg("count", count.into())
             .build();
 
@@ -121,18 +131,29 @@ impl Song {
         folder_id: U,
     ) -> Result<Vec<Song>>
     where
-        U: Into<Option<u64>>,
+        U: Into<Option<MusicFolderId>>,
     {
-        let args = Query::with("genre", genre)
-            .arg("count", page.count)
-            .arg("offset", page.offset)
-            .arg("musicFolderId", folder_id.into())
-            .build();
-
+        let args = genre_query(genre, page, folder_id.into());
         let song = client.get("getSongsByGenre", args)?;
         Ok(get_list_as!(song, Song))
     }
 
+    /// Returns an iterator over every song in a provided genre.
+    ///
+    /// Songs are fetched from the server a page at a time as the iterator
+    /// needs them. See [`Pages`] for details.
+    ///
+    /// [`Pages`]: ../search/struct.Pages.html
+    pub fn iter_in_genre<'a, U>(client: &'a Client, genre: &'a str, folder_id: U) -> Pages<'a, Song>
+    where
+        U: Into<Option<MusicFolderId>>,
+    {
+        let folder_id = folder_id.into();
+        Pages::new(search::ALL, move |page| {
+            Song::list_in_genre(client, genre, page, folder_id.clone())
+        })
+    }
+
     /// Creates an HLS (HTTP Live Streaming) playlist used for streaming video
     /// or audio. HLS is a streaming protocol implemented by Apple and works by
     /// breaking the overall stream into a sequence of small HTTP-based file
@@ -148,34 +169,200 @@ impl Song {
     /// empty array) to disable adaptive streaming, or given a single value to
     /// force streaming at that bit rate.
     pub fn hls(&self, client: &Client, bit_rates: &[u64]) -> Result<HlsPlaylist> {
-        let args = Query::with("id", self.id)
+        let args = Query::with("id", &self.id)
             .arg_list("bitrate", bit_rates)
             .build();
 
         let raw = client.get_raw("hls", args)?;
         Ok(raw.parse::<HlsPlaylist>()?)
     }
+
+    /// Bookmarks a position in the song, in milliseconds, replacing any
+    /// existing bookmark.
+    ///
+    /// See [`Bookmark`] for details.
+    ///
+    /// [`Bookmark`]: ../struct.Bookmark.html
+    pub fn bookmark<'a, S>(&self, client: &Client, position: u64, comment: S) -> Result<()>
+    where
+        S: Into<Option<&'a str>>,
+    {
+        Bookmark::create(client, &self.id, position, comment)
+    }
+}
+
+#[cfg(feature = "async")]
+impl Song {
+    /// Returns a single song from the Subsonic server without blocking.
+    ///
+    /// The asynchronous counterpart to [`get`](#method.get).
+    pub fn get_async<I>(client: &AsyncClient, id: I) -> SunkFuture<Song>
+    where
+        I: Into<SongId>,
+    {
+        Box::new(
+            client
+                .get("getSong", Query::with("id", id.into()))
+                .and_then(|res| Ok(serde_json::from_value(res)?)),
+        )
+    }
+
+    /// Returns a number of random songs similar to this one without blocking.
+    ///
+    /// The asynchronous counterpart to [`similar`](#method.similar).
+    pub fn similar_async<U>(&self, client: &AsyncClient, count: U) -> SunkFuture<Vec<Song>>
+    where
+        U: Into<Option<usize>>,
+    {
+        let args = Query::with("id", &self.id)
+            .arg("count", count.into())
+            .build();
+
+        Box::new(
+            client
+                .get("getSimilarSongs2", args)
+                .and_then(|song| -> Result<_> { Ok(get_list_as!(song, Song)) }),
+        )
+    }
+
+    /// Returns a number of random songs without blocking.
+    ///
+    /// The asynchronous counterpart to [`random`](#method.random).
+    pub fn random_async<U>(client: &AsyncClient, size: U) -> SunkFuture<Vec<Song>>
+    where
+        U: Into<Option<usize>>,
+    {
+        let arg = Query::with("size", size.into().unwrap_or(10));
+        Box::new(
+            client
+                .get("getRandomSongs", arg)
+                .and_then(|song| -> Result<_> { Ok(get_list_as!(song, Song)) }),
+        )
+    }
+
+    /// Creates a new builder to request a set of random songs without
+    /// blocking.
+    ///
+    /// The asynchronous counterpart to [`random_with`](#method.random_with).
+    pub fn random_with_async<'a>(client: &'a AsyncClient) -> RandomSongs<'a, AsyncClient> {
+        RandomSongs::new(client, 10)
+    }
+
+    /// Lists all the songs in a provided genre without blocking.
+    ///
+    /// The asynchronous counterpart to [`list_in_genre`](#method.list_in_genre).
+    pub fn list_in_genre_async<U>(
+        client: &AsyncClient,
+        genre: &str,
+        page: SearchPage,
+        folder_id: U,
+    ) -> SunkFuture<Vec<Song>>
+    where
+        U: Into<Option<MusicFolderId>>,
+    {
+        let args = genre_query(genre, page, folder_id.into());
+        Box::new(
+            client
+                .get("getSongsByGenre", args)
+                .and_then(|song| -> Result<_> { Ok(get_list_as!(song, Song)) }),
+        )
+    }
+
+    /// Creates an HLS (HTTP Live Streaming) playlist without blocking.
+    ///
+    /// The asynchronous counterpart to [`hls`](#method.hls).
+    pub fn hls_async(&self, client: &AsyncClient, bit_rates: &[u64]) -> SunkFuture<HlsPlaylist> {
+        let args = Query::with("id", &self.id)
+            .arg_list("bitrate", bit_rates)
+            .build();
+
+        Box::new(
+            client
+                .get_raw("hls", args)
+                .and_then(|raw| raw.parse::<HlsPlaylist>()),
+        )
+    }
+
+    /// Bookmarks a position in the song without blocking.
+    ///
+    /// The asynchronous counterpart to [`bookmark`](#method.bookmark).
+    pub fn bookmark_async<'a, S>(
+        &self,
+        client: &AsyncClient,
+        position: u64,
+        comment: S,
+    ) -> SunkFuture<()>
+    where
+        S: Into<Option<&'a str>>,
+    {
+        Bookmark::create_async(client, &self.id, position, comment)
+    }
+}
+
+/// Builds the arguments for a `getSongsByGenre` query.
+fn genre_query(genre: &str, page: SearchPage, folder_id: Option<MusicFolderId>) -> Query {
+    Query::with("genre", genre)
+        .arg("count", page.count)
+        .arg("offset", page.offset)
+        .arg("musicFolderId", folder_id)
+        .build()
+}
+
+impl Song {
+    fn stream_query(&self) -> Query {
+        let mut q = Query::with("id", &self.id);
+        q.arg("maxBitRate", self.stream_br);
+        q
+    }
 }
 
 impl Streamable for Song {
     fn stream(&self, client: &Client) -> Result<Vec<u8>> {
-        let mut q = Query::with("id", self.id);
-        q.arg("maxBitRate", self.stream_br);
-        client.get_bytes("stream", q)
+        client.get_bytes("stream", self.stream_query())
+    }
+
+    fn stream_reader(&self, client: &Client) -> Result<MediaReader> {
+        client.get_reader("stream", self.stream_query())
+    }
+
+    #[cfg(feature = "async")]
+    fn stream_async(&self, client: &AsyncClient) -> SunkFuture<Vec<u8>> {
+        client.get_bytes("stream", self.stream_query())
+    }
+
+    #[cfg(feature = "async")]
+    fn stream_chunks_async(&self, client: &AsyncClient) -> SunkStream<Vec<u8>> {
+        client.get_chunks("stream", self.stream_query())
     }
 
     fn stream_url(&self, client: &Client) -> Result<String> {
-        let mut q = Query::with("id", self.id);
-        q.arg("maxBitRate", self.stream_br);
-        client.build_url("stream", q)
+        client.build_url("stream", self.stream_query())
     }
 
     fn download(&self, client: &Client) -> Result<Vec<u8>> {
-        client.get_bytes("download", Query::with("id", self.id))
+        client.get_bytes("download", Query::with("id", &self.id))
+    }
+
+    fn download_reader(&self, client: &Client) -> Result<MediaReader> {
+        client.get_reader("download", Query::with("id", &self.id))
+    }
+
+    #[cfg(feature = "async")]
+    fn download_async(&self, client: &AsyncClient) -> SunkFuture<Vec<u8>> {
+        client.get_bytes("download", Query::with("id", &self.id))
+    }
+
+    #[cfg(feature = "async")]
+    fn download_chunks_async(&self, client: &AsyncClient) -> SunkStream<Vec<u8>> {
+        client.get_chunks("download", Query::with("id", &self.id))
     }
 
     fn download_url(&self, client: &Client) -> Result<String> {
-        client.build_url("download", Query::with("id", self.id))
+        client.build_url("download", Query::with("id", &self.id))
+    }
+
+    fn download_size(&self) -> Option<u64> {
+        Some(self.size)
     }
 
     fn encoding(&self) -> &str {
@@ -253,7 +440,7 @@ impl<'de> Deserialize<'de> for Song {
         #[derive(Debug, Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct _Song {
-            id: String,
+            id: SongId,
             parent: String,
             is_dir: bool,
             title: String,
@@ -272,11 +459,14 @@ impl<'de> Deserialize<'de> for Song {
             bit_rate: Option<u64>,
             path: String,
             is_video: Option<bool>,
+            // Omitted by servers for songs that have never been played.
+            #[serde(default)]
             play_count: u64,
             disc_number: Option<u64>,
             created: String,
-            album_id: Option<String>,
-            artist_id: Option<String>,
+            starred: Option<String>,
+            album_id: Option<AlbumId>,
+            artist_id: Option<ArtistId>,
             #[serde(rename = "type")]
             media_type: String,
         }
@@ -284,12 +474,12 @@ impl<'de> Deserialize<'de> for Song {
         let raw = _Song::deserialize(de)?;
 
         Ok(Song {
-            id: raw.id.parse().unwrap(),
+            id: raw.id,
             title: raw.title,
             album: raw.album,
-            album_id: raw.album_id.map(|i| i.parse().unwrap()),
+            album_id: raw.album_id,
             artist: raw.artist,
-            artist_id: raw.artist_id.map(|i| i.parse().unwrap()),
+            artist_id: raw.artist_id,
             cover_id: raw.cover_art,
             track: raw.track,
             year: raw.year,
@@ -300,6 +490,7 @@ impl<'de> Deserialize<'de> for Song {
             transcoded_content_type: raw.transcoded_content_type,
             transcoded_suffix: raw.transcoded_suffix,
             duration: raw.duration,
+            starred: raw.starred,
             path: raw.path,
             media_type: raw.media_type,
             stream_br: None,
@@ -358,17 +549,17 @@ pub struct Lyrics {
 /// # fn main() { }
 /// ```
 #[derive(Debug)]
-pub struct RandomSongs<'a> {
-    client: &'a Client,
+pub struct RandomSongs<'a, C: 'a = Client> {
+    client: &'a C,
     size: usize,
     genre: Option<&'a str>,
     from_year: Option<usize>,
     to_year: Option<usize>,
-    folder_id: Option<usize>,
+    folder_id: Option<MusicFolderId>,
 }
 
-impl<'a> RandomSongs<'a> {
-    fn new(client: &'a Client, n: usize) -> RandomSongs<'a> {
+impl<'a, C> RandomSongs<'a, C> {
+    fn new(client: &'a C, n: usize) -> RandomSongs<'a, C> {
         RandomSongs {
             client,
             size: n,
@@ -380,7 +571,7 @@ impl<'a> RandomSongs<'a> {
     }
 
     /// Sets the number of songs to return.
-    pub fn size(&mut self, n: usize) -> &mut RandomSongs<'a> {
+    pub fn size(&mut self, n: usize) -> &mut RandomSongs<'a, C> {
         self.size = n;
         self
     }
@@ -391,19 +582,19 @@ impl<'a> RandomSongs<'a> {
     /// [`Client::genres`] method.
     ///
     /// [`Client::genres`]: ../struct.Client.html#method.genres
-    pub fn genre(&mut self, genre: &'a str) -> &mut RandomSongs<'a> {
+    pub fn genre(&mut self, genre: &'a str) -> &mut RandomSongs<'a, C> {
         self.genre = Some(genre);
         self
     }
 
     /// Sets a lower bound on the year that songs were released in.
-    pub fn from_year(&mut self, year: usize) -> &mut RandomSongs<'a> {
+    pub fn from_year(&mut self, year: usize) -> &mut RandomSongs<'a, C> {
         self.from_year = Some(year);
         self
     }
 
     /// Sets an upper bound on the year that songs were released in.
-    pub fn to_year(&mut self, year: usize) -> &mut RandomSongs<'a> {
+    pub fn to_year(&mut self, year: usize) -> &mut RandomSongs<'a, C> {
         self.to_year = Some(year);
         self
     }
@@ -413,39 +604,58 @@ impl<'a> RandomSongs<'a> {
     /// The range is set *inclusive* at both ends, unlike a standard Rust
     /// range. For example, a range `2013..2016` will return songs that
     /// were released in 2013, 2014, 2015, and 2016.
-    pub fn in_years(&mut self, years: Range<usize>) -> &mut RandomSongs<'a> {
+    pub fn in_years(&mut self, years: Range<usize>) -> &mut RandomSongs<'a, C> {
         self.from_year = Some(years.start);
         self.to_year = Some(years.end);
         self
     }
 
-    /// Sets the folder index that songs must be in.
+    /// Sets the folder that songs must be in.
     ///
-    /// Music folders are zero-indexed, and there will always be index `0`
-    /// (provided the server is configured at all) . A list of music
-    /// folders can be found using the [`Client::music_folders`] method.
+    /// A list of music folders can be found using the
+    /// [`Client::music_folders`] method.
     ///
     /// [`Client::music_folders`]: ../struct.Client.html#method.music_folders
-    pub fn in_folder(&mut self, id: usize) -> &mut RandomSongs<'a> {
-        self.folder_id = Some(id);
+    pub fn in_folder<I>(&mut self, id: I) -> &mut RandomSongs<'a, C>
+    where
+        I: Into<MusicFolderId>,
+    {
+        self.folder_id = Some(id.into());
         self
     }
 
-    /// Issues the query to the Subsonic server. Returns a list of random
-    /// songs, modified by the builder.
-    pub fn request(&mut self) -> Result<Vec<Song>> {
-        let args = Query::with("size", self.size)
+    fn query(&self) -> Query {
+        Query::with("size", self.size)
             .arg("genre", self.genre)
             .arg("fromYear", self.from_year)
             .arg("toYear", self.to_year)
-            .arg("musicFolderId", self.folder_id)
-            .build();
+            .arg("musicFolderId", self.folder_id.as_ref())
+            .build()
+    }
+}
 
-        let song = self.client.get("getRandomSongs", args)?;
+impl<'a> RandomSongs<'a, Client> {
+    /// Issues the query to the Subsonic server. Returns a list of random
+    /// songs, modified by the builder.
+    pub fn request(&mut self) -> Result<Vec<Song>> {
+        let song = self.client.get("getRandomSongs", self.query())?;
         Ok(get_list_as!(song, Song))
     }
 }
 
+#[cfg(feature = "async")]
+impl<'a> RandomSongs<'a, AsyncClient> {
+    /// Issues the query to the Subsonic server without blocking. Resolves to
+    /// a list of random songs, modified by the builder.
+    pub fn request(&mut self) -> SunkFuture<Vec<Song>> {
+        Box::new(
+            self.client
+                .get("getRandomSongs", self.query())
+                .and_then(|song| -> Result<_> { Ok(get_list_as!(song, Song)) }),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -455,7 +665,7 @@ mod tests {
     fn parse_song() {
         let parsed = serde_json::from_value::<Song>(raw()).unwrap();
 
-        assert_eq!(parsed.id, 27);
+        assert_eq!(parsed.id, "27");
         assert_eq!(parsed.title, String::from("Bellevue Avenue"));
         assert_eq!(parsed.track, Some(1));
     }
@@ -1,14 +1,19 @@
-use serde::de::{Deserialize, Deserializer};
+use serde::de::{self, Deserialize, Deserializer};
 use serde_json;
 use std::result;
 
+#[cfg(feature = "async")]
+use futures::Future;
+use id::{AudioTrackId, CaptionsId, ConversionId, DirectoryId, VideoId};
 use query::Query;
-use {Client, Error, Media, Result, Streamable};
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture, SunkStream};
+use {Client, Error, Media, MediaReader, Result, Streamable};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Video {
-    pub id: usize,
-    parent: usize,
+    pub id: VideoId,
+    parent: DirectoryId,
     is_dir: bool,
     pub title: String,
     pub album: Option<String>,
@@ -35,7 +40,11 @@ pub struct Video {
 }
 
 impl Video {
-    pub fn get(client: &Client, id: usize) -> Result<Video> {
+    pub fn get<I>(client: &Client, id: I) -> Result<Video>
+    where
+        I: Into<VideoId>,
+    {
+        let id = id.into();
         Video::list(client)?
             .into_iter()
             .find(|v| v.id == id)
@@ -51,7 +60,7 @@ impl Video {
     where
         S: Into<Option<&'a str>>,
     {
-        let args = Query::with("id", self.id)
+        let args = Query::with("id", &self.id)
             .arg("format", format.into())
             .build();
         let res = client.get("getVideoInfo", args)?;
@@ -63,13 +72,74 @@ impl Video {
     where
         S: Into<Option<&'a str>>,
     {
-        let args = Query::with("id", self.id)
+        let args = Query::with("id", &self.id)
             .arg("format", format.into())
             .build();
         let res = client.get_raw("getCaptions", args)?;
         Ok(res)
     }
 
+    /// Fetches a single video without blocking.
+    ///
+    /// The asynchronous counterpart to [`get`](#method.get).
+    #[cfg(feature = "async")]
+    pub fn get_async<I>(client: &AsyncClient, id: I) -> SunkFuture<Video>
+    where
+        I: Into<VideoId>,
+    {
+        let id = id.into();
+        Box::new(Video::list_async(client).and_then(move |videos| {
+            videos
+                .into_iter()
+                .find(|v| v.id == id)
+                .ok_or_else(|| Error::Other("no video found"))
+        }))
+    }
+
+    /// Lists all videos on the server without blocking.
+    ///
+    /// The asynchronous counterpart to [`list`](#method.list).
+    #[cfg(feature = "async")]
+    pub fn list_async(client: &AsyncClient) -> SunkFuture<Vec<Video>> {
+        Box::new(
+            client
+                .get("getVideos", Query::none())
+                .and_then(|video| -> Result<_> { Ok(get_list_as!(video, Video)) }),
+        )
+    }
+
+    /// Fetches details about the video without blocking.
+    ///
+    /// The asynchronous counterpart to [`info`](#method.info).
+    #[cfg(feature = "async")]
+    pub fn info_async<'a, S>(&self, client: &AsyncClient, format: S) -> SunkFuture<VideoInfo>
+    where
+        S: Into<Option<&'a str>>,
+    {
+        let args = Query::with("id", &self.id)
+            .arg("format", format.into())
+            .build();
+        Box::new(
+            client
+                .get("getVideoInfo", args)
+                .and_then(|res| Ok(serde_json::from_value(res)?)),
+        )
+    }
+
+    /// Returns the raw video captions without blocking.
+    ///
+    /// The asynchronous counterpart to [`captions`](#method.captions).
+    #[cfg(feature = "async")]
+    pub fn captions_async<'a, S>(&self, client: &AsyncClient, format: S) -> SunkFuture<String>
+    where
+        S: Into<Option<&'a str>>,
+    {
+        let args = Query::with("id", &self.id)
+            .arg("format", format.into())
+            .build();
+        client.get_raw("getCaptions", args)
+    }
+
     /// Sets the size that the video will stream at, measured in pixels.
     pub fn set_size(&mut self, width: usize, height: usize) {
         self.stream_size = Some((width, height));
@@ -85,37 +155,66 @@ impl Video {
     }
 }
 
-impl Streamable for Video {
-    fn stream(&self, client: &Client) -> Result<Vec<u8>> {
-        let args = Query::with("id", self.id)
+impl Video {
+    fn stream_query(&self) -> Query {
+        Query::with("id", &self.id)
             .arg("maxBitRate", self.stream_br)
             .arg(
                 "size",
                 self.stream_size.map(|(w, h)| format!("{}x{}", w, h)),
             )
             .arg("timeOffset", self.stream_offset)
-            .build();
-        client.get_bytes("stream", args)
+            .build()
+    }
+}
+
+impl Streamable for Video {
+    fn stream(&self, client: &Client) -> Result<Vec<u8>> {
+        client.get_bytes("stream", self.stream_query())
+    }
+
+    fn stream_reader(&self, client: &Client) -> Result<MediaReader> {
+        client.get_reader("stream", self.stream_query())
+    }
+
+    #[cfg(feature = "async")]
+    fn stream_async(&self, client: &AsyncClient) -> SunkFuture<Vec<u8>> {
+        client.get_bytes("stream", self.stream_query())
+    }
+
+    #[cfg(feature = "async")]
+    fn stream_chunks_async(&self, client: &AsyncClient) -> SunkStream<Vec<u8>> {
+        client.get_chunks("stream", self.stream_query())
     }
 
     fn stream_url(&self, client: &Client) -> Result<String> {
-        let args = Query::with("id", self.id)
-            .arg("maxBitRate", self.stream_br)
-            .arg(
-                "size",
-                self.stream_size.map(|(w, h)| format!("{}x{}", w, h)),
-            )
-            .arg("timeOffset", self.stream_offset)
-            .build();
-        client.build_url("stream", args)
+        client.build_url("stream", self.stream_query())
     }
 
     fn download(&self, client: &Client) -> Result<Vec<u8>> {
-        client.get_bytes("download", Query::with("id", self.id))
+        client.get_bytes("download", Query::with("id", &self.id))
+    }
+
+    fn download_reader(&self, client: &Client) -> Result<MediaReader> {
+        client.get_reader("download", Query::with("id", &self.id))
+    }
+
+    #[cfg(feature = "async")]
+    fn download_async(&self, client: &AsyncClient) -> SunkFuture<Vec<u8>> {
+        client.get_bytes("download", Query::with("id", &self.id))
+    }
+
+    #[cfg(feature = "async")]
+    fn download_chunks_async(&self, client: &AsyncClient) -> SunkStream<Vec<u8>> {
+        client.get_chunks("download", Query::with("id", &self.id))
     }
 
     fn download_url(&self, client: &Client) -> Result<String> {
-        client.build_url("download", Query::with("id", self.id))
+        client.build_url("download", Query::with("id", &self.id))
+    }
+
+    fn download_size(&self) -> Option<u64> {
+        Some(self.size as u64)
     }
 
     fn encoding(&self) -> &str {
@@ -169,8 +268,8 @@ impl<'de> Deserialize<'de> for Video {
         #[derive(Debug, Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct _Video {
-            id: String,
-            parent: String,
+            id: VideoId,
+            parent: DirectoryId,
             is_dir: bool,
             title: String,
             album: Option<String>,
@@ -196,8 +295,8 @@ impl<'de> Deserialize<'de> for Video {
         let raw = _Video::deserialize(de)?;
 
         Ok(Video {
-            id: raw.id.parse().unwrap(),
-            parent: raw.parent.parse().unwrap(),
+            id: raw.id,
+            parent: raw.parent,
             is_dir: raw.is_dir,
             title: raw.title,
             album: raw.album,
@@ -227,7 +326,7 @@ impl<'de> Deserialize<'de> for Video {
 
 #[derive(Debug)]
 pub struct VideoInfo {
-    pub id: usize,
+    pub id: VideoId,
     pub captions: Option<Captions>,
     pub audio_tracks: Vec<AudioTrack>,
     pub conversion: Option<Conversion>,
@@ -240,7 +339,7 @@ impl<'de> Deserialize<'de> for VideoInfo {
     {
         #[derive(Deserialize)]
         struct _VideoInfo {
-            id: String,
+            id: VideoId,
             captions: Option<Captions>,
             #[serde(rename = "audioTrack")]
             #[serde(default)]
@@ -249,7 +348,7 @@ impl<'de> Deserialize<'de> for VideoInfo {
         }
         let raw = _VideoInfo::deserialize(de)?;
         Ok(VideoInfo {
-            id: raw.id.parse().unwrap(),
+            id: raw.id,
             captions: raw.captions,
             audio_tracks: raw.audio_tracks,
             conversion: raw.conversion,
@@ -259,7 +358,7 @@ impl<'de> Deserialize<'de> for VideoInfo {
 
 #[derive(Debug)]
 pub struct AudioTrack {
-    pub id: usize,
+    pub id: AudioTrackId,
     pub name: String,
     pub language_code: String,
 }
@@ -271,14 +370,14 @@ impl<'de> Deserialize<'de> for AudioTrack {
     {
         #[derive(Deserialize)]
         struct _AudioTrack {
-            id: String,
+            id: AudioTrackId,
             name: String,
             #[serde(rename = "languageCode")]
             language_code: String,
         }
         let raw = _AudioTrack::deserialize(de)?;
         Ok(AudioTrack {
-            id: raw.id.parse().unwrap(),
+            id: raw.id,
             name: raw.name,
             language_code: raw.language_code,
         })
@@ -287,7 +386,7 @@ impl<'de> Deserialize<'de> for AudioTrack {
 
 #[derive(Debug)]
 pub struct Captions {
-    pub id: usize,
+    pub id: CaptionsId,
     pub name: String,
 }
 
@@ -298,12 +397,12 @@ impl<'de> Deserialize<'de> for Captions {
     {
         #[derive(Deserialize)]
         struct _Captions {
-            id: String,
+            id: CaptionsId,
             name: String,
         }
         let raw = _Captions::deserialize(de)?;
         Ok(Captions {
-            id: raw.id.parse().unwrap(),
+            id: raw.id,
             name: raw.name,
         })
     }
@@ -311,7 +410,7 @@ impl<'de> Deserialize<'de> for Captions {
 
 #[derive(Debug)]
 pub struct Conversion {
-    pub id: usize,
+    pub id: ConversionId,
     pub bitrate: usize,
 }
 
@@ -322,14 +421,27 @@ impl<'de> Deserialize<'de> for Conversion {
     {
         #[derive(Deserialize)]
         struct _Conversion {
-            id: String,
+            id: ConversionId,
             #[serde(rename = "bitRate")]
-            bitrate: String,
+            bitrate: _BitRate,
+        }
+
+        // Some servers send the bit rate as a string.
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum _BitRate {
+            Number(usize),
+            Text(String),
         }
+
         let raw = _Conversion::deserialize(de)?;
+        let bitrate = match raw.bitrate {
+            _BitRate::Number(n) => n,
+            _BitRate::Text(s) => s.parse().map_err(de::Error::custom)?,
+        };
         Ok(Conversion {
-            id: raw.id.parse().unwrap(),
-            bitrate: raw.bitrate.parse().unwrap(),
+            id: raw.id,
+            bitrate,
         })
     }
 }
@@ -342,7 +454,7 @@ mod tests {
     fn parse_video() {
         let parsed = serde_json::from_value::<Video>(raw()).unwrap();
 
-        assert_eq!(parsed.id, 460);
+        assert_eq!(parsed.id, "460");
         assert_eq!(parsed.title, "Big Buck Bunny");
         assert!(!parsed.has_cover_art());
     }
@@ -351,8 +463,30 @@ mod tests {
     fn parse_video_info() {
         let parsed = serde_json::from_value::<VideoInfo>(raw_info()).unwrap();
 
-        assert_eq!(parsed.id, 7058);
+        assert_eq!(parsed.id, "7058");
         assert_eq!(parsed.audio_tracks.len(), 5);
+        assert_eq!(parsed.audio_tracks[1].id, "3");
+        let conversion = parsed.conversion.unwrap();
+        assert_eq!(conversion.id, "37");
+        assert_eq!(conversion.bitrate, 1000);
+    }
+
+    #[test]
+    fn parse_numeric_video_info() {
+        let raw = r#"{
+            "id": 7058,
+            "captions": { "id": 0, "name": "Planes 2.srt" },
+            "audioTrack": [{ "id": 1, "name": "English", "languageCode": "eng" }],
+            "conversion": { "id": 37, "bitRate": 1000 }
+        }"#;
+        let parsed = serde_json::from_str::<VideoInfo>(raw).unwrap();
+
+        assert_eq!(parsed.captions.unwrap().id, "0");
+        assert_eq!(parsed.audio_tracks[0].id, "1");
+        assert_eq!(parsed.conversion.unwrap().bitrate, 1000);
+
+        let bad = r#"{ "id": "7058", "conversion": { "id": "37", "bitRate": "fast" } }"#;
+        assert!(serde_json::from_str::<VideoInfo>(bad).is_err());
     }
 
     fn raw() -> serde_json::Value {
@@ -0,0 +1,172 @@
+use serde::de::{Deserialize, Deserializer};
+use std::result;
+
+use id::SongId;
+use query::Query;
+use Song;
+
+/// The state of a user's play queue, as saved by a client.
+///
+/// Saving the play queue from one client and fetching it from another allows
+/// playback to be handed between devices. The queue is fetched with
+/// [`Client::play_queue`] and saved with [`Client::save_play_queue`].
+///
+/// [`Client::play_queue`]: ./struct.Client.html#method.play_queue
+/// [`Client::save_play_queue`]: ./struct.Client.html#method.save_play_queue
+#[derive(Debug, Clone)]
+pub struct PlayQueue {
+    /// The songs in the queue, in order.
+    pub songs: Vec<Song>,
+    /// The ID of the song that was playing when the queue was saved.
+    pub current: Option<SongId>,
+    /// The position in the current song, in milliseconds.
+    pub position: u64,
+    /// The user who owns the queue.
+    pub user: String,
+    /// When the queue was last saved.
+    pub changed: String,
+    /// The name of the client that last saved the queue.
+    pub changed_by: String,
+}
+
+impl PlayQueue {
+    /// Returns the song that was playing when the queue was saved.
+    pub fn current_song(&self) -> Option<&Song> {
+        let current = self.current.as_ref()?;
+        self.songs.iter().find(|s| s.id == *current)
+    }
+}
+
+impl<'de> Deserialize<'de> for PlayQueue {
+    fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct _PlayQueue {
+            #[serde(default)]
+            entry: Vec<Song>,
+            current: Option<SongId>,
+            #[serde(default)]
+            position: u64,
+            #[serde(default)]
+            username: String,
+            #[serde(default)]
+            changed: String,
+            #[serde(default)]
+            changed_by: String,
+        }
+
+        let raw = _PlayQueue::deserialize(de)?;
+
+        Ok(PlayQueue {
+            songs: raw.entry,
+            current: raw.current,
+            position: raw.position,
+            user: raw.username,
+            changed: raw.changed,
+            changed_by: raw.changed_by,
+        })
+    }
+}
+
+/// Builds the arguments for a `savePlayQueue` query.
+pub(crate) fn save_query(songs: &[Song], current: Option<SongId>, position: Option<u64>) -> Query {
+    let ids = songs.iter().map(|s| s.id.clone()).collect::<Vec<_>>();
+    Query::new()
+        .arg_list("id", &ids)
+        .arg("current", current)
+        .arg("position", position)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+    use test_util;
+    use Client;
+
+    #[test]
+    fn parse_play_queue() {
+        let parsed = serde_json::from_value::<PlayQueue>(raw()).unwrap();
+        assert_eq!(parsed.songs.len(), 1);
+        assert_eq!(parsed.current, Some(SongId::from(27)));
+        assert_eq!(parsed.position, 45_000);
+        assert_eq!(parsed.changed_by, "android");
+        assert_eq!(parsed.current_song().map(|s| s.id.as_str()), Some("27"));
+    }
+
+    #[test]
+    fn parse_string_current() {
+        let mut raw = raw();
+        raw["current"] = serde_json::Value::String("27".into());
+        let parsed = serde_json::from_value::<PlayQueue>(raw).unwrap();
+        assert_eq!(parsed.current, Some(SongId::from(27)));
+    }
+
+    #[test]
+    fn save_and_fetch_play_queue() {
+        let body = format!(r#""playQueue": {}"#, raw());
+        let (site, heads) = test_util::serve(vec![
+            test_util::ok_response(""),
+            test_util::ok_response(&body),
+        ]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+        let queue = serde_json::from_value::<PlayQueue>(raw()).unwrap();
+
+        cli.save_play_queue(&queue.songs, queue.current.clone(), 45_000)
+            .unwrap();
+        let head = heads.recv().unwrap();
+        assert!(head.contains("/rest/savePlayQueue?"));
+        assert!(head.contains("&id=27&current=27&position=45000 "));
+
+        let fetched = cli.play_queue().unwrap().unwrap();
+        assert!(heads.recv().unwrap().contains("/rest/getPlayQueue?"));
+        assert_eq!(fetched.user, "guest3");
+    }
+
+    #[test]
+    fn no_saved_play_queue() {
+        let (site, heads) = test_util::serve(vec![test_util::ok_response("")]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+
+        assert!(cli.play_queue().unwrap().is_none());
+        assert!(heads.recv().unwrap().contains("/rest/getPlayQueue?"));
+    }
+
+    fn raw() -> serde_json::Value {
+        serde_json::from_str(
+            r#"{
+            "current" : 27,
+            "position" : 45000,
+            "username" : "guest3",
+            "changed" : "2018-02-03T06:22:27.411Z",
+            "changedBy" : "android",
+            "entry" : [ {
+                "id" : "27",
+                "parent" : "25",
+                "isDir" : false,
+                "title" : "Bellevue Avenue",
+                "album" : "Bellevue",
+                "artist" : "Misteur Valaire",
+                "track" : 1,
+                "coverArt" : "25",
+                "size" : 5400185,
+                "contentType" : "audio/mpeg",
+                "suffix" : "mp3",
+                "duration" : 198,
+                "bitRate" : 216,
+                "path" : "Misteur Valaire/Bellevue/01 - Misteur Valaire - Bellevue Avenue.mp3",
+                "playCount" : 706,
+                "created" : "2017-03-12T11:07:27.000Z",
+                "albumId" : "1",
+                "artistId" : "1",
+                "type" : "music"
+            } ]
+        }"#,
+        )
+        .unwrap()
+    }
+}
@@ -1,7 +1,8 @@
 use std::{fmt, iter};
+use url::form_urlencoded;
 
 /// An expandable query set for an API call.
-#[derive(Debug, PartialEq, PartialOrd)]
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
 pub struct Query {
     inner: Vec<(String, Arg)>,
 }
@@ -105,19 +106,33 @@ impl iter::Extend<(String, Arg)> for Query {
 }
 
 impl fmt::Display for Query {
+    /// Writes the query in `application/x-www-form-urlencoded` form, skipping
+    /// any arguments without a value.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for (n, a) in self.inner.iter().enumerate() {
-            if a.1.is_some() {
-                write!(f, "{}={}", a.0, a.1)?;
-                if n + 1 < self.inner.len() {
-                    write!(f, "&")?;
-                }
-            }
+        let mut args = self.inner.iter().filter(|a| a.1.is_some());
+        if let Some(first) = args.next() {
+            write_pair(f, first)?;
+        }
+        for a in args {
+            write!(f, "&")?;
+            write_pair(f, a)?;
         }
         Ok(())
     }
 }
 
+fn write_pair(f: &mut fmt::Formatter, (key, arg): &(String, Arg)) -> fmt::Result {
+    let value = arg.0.as_deref().unwrap_or_default();
+    for s in form_urlencoded::byte_serialize(key.as_bytes()) {
+        f.write_str(s)?;
+    }
+    f.write_str("=")?;
+    for s in form_urlencoded::byte_serialize(value.as_bytes()) {
+        f.write_str(s)?;
+    }
+    Ok(())
+}
+
 impl Default for Query {
     fn default() -> Query {
         Query::new()
@@ -238,4 +253,47 @@ mod tests {
         q.arg_list("id", ids);
         assert_eq!("id=1&id=2&id=3&id=4", &format!("{}", q))
     }
+
+    fn round_trip(q: &Query) -> Vec<(String, String)> {
+        form_urlencoded::parse(q.to_string().as_bytes())
+            .into_owned()
+            .collect()
+    }
+
+    #[test]
+    fn reserved_characters_are_encoded() {
+        let q = Query::new()
+            .arg("query", "AC/DC & Friends")
+            .arg("name", "Rock #1")
+            .arg("password", "a=b&c+d%")
+            .build();
+        assert_eq!(
+            "query=AC%2FDC+%26+Friends&name=Rock+%231&password=a%3Db%26c%2Bd%25",
+            &format!("{}", q)
+        );
+        assert_eq!(
+            round_trip(&q),
+            vec![
+                ("query".to_string(), "AC/DC & Friends".to_string()),
+                ("name".to_string(), "Rock #1".to_string()),
+                ("password".to_string(), "a=b&c+d%".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unicode_round_trips() {
+        let q = Query::new()
+            .arg("title", "Björk — Jóga")
+            .arg("artist", "坂本龍一")
+            .build();
+        assert!(q.to_string().is_ascii());
+        assert_eq!(
+            round_trip(&q),
+            vec![
+                ("title".to_string(), "Björk — Jóga".to_string()),
+                ("artist".to_string(), "坂本龍一".to_string()),
+            ]
+        );
+    }
 }
@@ -1,6 +1,20 @@
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
 use serde_json;
 
-use ApiError;
+use {ApiError, Error, Result, ServerInfo};
+
+/// Returns `true` if the headers mark the body as JSON.
+///
+/// Endpoints that return raw media (streams, downloads, cover art) answer with
+/// a regular JSON response when they fail, so this is used to tell the two
+/// apart.
+pub(crate) fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|ct| ct.to_str().ok())
+        .map(|ct| ct.starts_with("application/json") || ct.starts_with("text/json"))
+        .unwrap_or(false)
+}
 
 /// A top-level response from a Subsonic server.
 #[derive(Debug, Deserialize)]
@@ -15,6 +29,11 @@ pub struct Response {
 struct InnerResponse {
     status: String,
     version: String,
+    #[serde(default)]
+    open_subsonic: bool,
+    #[serde(rename = "type")]
+    server_type: Option<String>,
+    server_version: Option<String>,
     error: Option<ApiError>,
     license: Option<serde_json::Value>,
     music_folders: Option<serde_json::Value>,
@@ -39,6 +58,7 @@ struct InnerResponse {
     random_songs: Option<serde_json::Value>,
     songs_by_genre: Option<serde_json::Value>,
     now_playing: Option<serde_json::Value>,
+    open_subsonic_extensions: Option<serde_json::Value>,
     starred: Option<serde_json::Value>,
     starred2: Option<serde_json::Value>,
     search_result: Option<serde_json::Value>,
@@ -108,6 +128,7 @@ impl Response {
             music_folders,
             newest_podcasts,
             now_playing,
+            open_subsonic_extensions,
             play_queue,
             playlist,
             playlists,
@@ -133,6 +154,31 @@ impl Response {
         None
     }
 
+    /// Converts the response into a `Result`, yielding the internal value of a
+    /// successful response or the API error of a failed one.
+    ///
+    /// A successful response without a body results in `Value::Null`.
+    pub fn into_result(self) -> Result<serde_json::Value> {
+        if self.is_ok() {
+            Ok(self.into_value().unwrap_or(serde_json::Value::Null))
+        } else {
+            Err(self
+                .into_error()
+                .map(|e| e.into())
+                .ok_or_else(|| Error::Other("unable to retrieve error"))?)
+        }
+    }
+
+    /// Returns the details the server sent about itself.
+    pub fn server(&self) -> ServerInfo {
+        ServerInfo {
+            version: self.inner.version.parse().ok(),
+            open_subsonic: self.inner.open_subsonic,
+            server_type: self.inner.server_type.clone(),
+            server_version: self.inner.server_version.clone(),
+        }
+    }
+
     /// Extracts the error struct of the response. Returns `None` if the
     /// response was not a failure.
     pub fn into_error(self) -> Option<ApiError> {
@@ -157,6 +203,7 @@ impl Response {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use Version;
 
     #[test]
     fn into_err_result() {
@@ -176,6 +223,23 @@ mod tests {
             "version": "1.14.0"
         }}"#;
         let success = serde_json::from_str::<Response>(success).unwrap();
+        assert!(!success.server().open_subsonic);
         assert!(success.into_error().is_none());
     }
+
+    #[test]
+    fn open_subsonic_server() {
+        let res = r#"{"subsonic-response": {
+            "status": "ok",
+            "version": "1.16.1",
+            "type": "navidrome",
+            "serverVersion": "0.53.3 (13af8ed4)",
+            "openSubsonic": true
+        }}"#;
+        let server = serde_json::from_str::<Response>(res).unwrap().server();
+        assert_eq!(server.version, Some(Version::new(1, 16, 1)));
+        assert!(server.open_subsonic);
+        assert_eq!(server.server_type, Some("navidrome".into()));
+        assert_eq!(server.server_version, Some("0.53.3 (13af8ed4)".into()));
+    }
 }
@@ -2,8 +2,8 @@
 //!
 //! The Subsonic API works on the concept of paging, something not uncommon in
 //! RESTful APIs. A search will return a number of results up to a
-//! specification. The client then has a virtual "page" number they will send,
-//! to offset a search by a multiple of the return number.
+//! specification. The client then sends an offset to skip the results it has
+//! already seen, moving through the results a page at a time.
 //!
 //! # Example
 //!
@@ -22,23 +22,23 @@
 //! let mut page = SearchPage::new();
 //! let list = ListType::default();
 //!
-//! let results = Album::list(&client, list, page, 0)?;
+//! let results = Album::list(&client, &list, page, None)?;
 //! assert_eq!(results.len(), 20);
 //! #
 //! # page.next();
-//! # let more_results = Album::list(&client, list, page, 0)?;
+//! # let more_results = Album::list(&client, &list, page, None)?;
 //! # assert_eq!(more_results.len(), 20);
 //! #
 //! # page.next();
-//! # let last_results = Album::list(&client, list, page, 0)?;
+//! # let last_results = Album::list(&client, &list, page, None)?;
 //! # assert_eq!(last_results.len(), 10);
 //! #
 //! # let exact = SearchPage::new().with_size(50);
-//! # let exact_results = Album::list(&client, list, exact, 0)?;
+//! # let exact_results = Album::list(&client, &list, exact, None)?;
 //! # assert_eq!(exact_results.len(), 50);
 //! #
 //! # let all = search::ALL;
-//! # let all_results = Album::list(&client, list, all, 0)?;
+//! # let all_results = Album::list(&client, &list, all, None)?;
 //! # assert_eq!(all_results.len(), 50);
 //! #
 //! # Ok(())
@@ -61,23 +61,23 @@
 //! # let mut page = SearchPage::new();
 //! # let list = ListType::default();
 //! #
-//! # let results = Album::list(&client, list, page, 0)?;
+//! # let results = Album::list(&client, &list, page, None)?;
 //! # assert_eq!(results.len(), 20);
 //! #
 //! page.next();
-//! let more_results = Album::list(&client, list, page, 0)?;
+//! let more_results = Album::list(&client, &list, page, None)?;
 //! assert_eq!(more_results.len(), 20);
 //!
 //! page.next();
-//! let last_results = Album::list(&client, list, page, 0)?;
+//! let last_results = Album::list(&client, &list, page, None)?;
 //! assert_eq!(last_results.len(), 10);
 //! #
 //! # let exact = SearchPage::new().with_size(50);
-//! # let exact_results = Album::list(&client, list, exact, 0)?;
+//! # let exact_results = Album::list(&client, &list, exact, None)?;
 //! # assert_eq!(exact_results.len(), 50);
 //! #
 //! # let all = search::ALL;
-//! # let all_results = Album::list(&client, list, all, 0)?;
+//! # let all_results = Album::list(&client, &list, all, None)?;
 //! # assert_eq!(all_results.len(), 50);
 //! #
 //! # Ok(())
@@ -104,23 +104,23 @@
 //! # let mut page = SearchPage::new();
 //! # let list = ListType::default();
 //! #
-//! # let results = Album::list(&client, list, page, 0)?;
+//! # let results = Album::list(&client, &list, page, None)?;
 //! # assert_eq!(results.len(), 20);
 //! #
 //! # page.next();
-//! # let more_results = Album::list(&client, list, page, 0)?;
+//! # let more_results = Album::list(&client, &list, page, None)?;
 //! # assert_eq!(more_results.len(), 20);
 //! #
 //! # page.next();
-//! # let last_results = Album::list(&client, list, page, 0)?;
+//! # let last_results = Album::list(&client, &list, page, None)?;
 //! # assert_eq!(last_results.len(), 10);
 //! #
 //! let exact = SearchPage::new().with_size(50);
-//! let exact_results = Album::list(&client, list, exact, 0)?;
+//! let exact_results = Album::list(&client, &list, exact, None)?;
 //! assert_eq!(exact_results.len(), 50);
 //! #
 //! # let all = search::ALL;
-//! # let all_results = Album::list(&client, list, all, 0)?;
+//! # let all_results = Album::list(&client, &list, all, None)?;
 //! # assert_eq!(all_results.len(), 50);
 //! #
 //! # Ok(())
@@ -146,33 +146,57 @@
 //! # let mut page = SearchPage::new();
 //! # let list = ListType::default();
 //! #
-//! # let results = Album::list(&client, list, page, 0)?;
+//! # let results = Album::list(&client, &list, page, None)?;
 //! # assert_eq!(results.len(), 20);
 //! #
 //! # page.next();
-//! # let more_results = Album::list(&client, list, page, 0)?;
+//! # let more_results = Album::list(&client, &list, page, None)?;
 //! # assert_eq!(more_results.len(), 20);
 //! #
 //! # page.next();
-//! # let last_results = Album::list(&client, list, page, 0)?;
+//! # let last_results = Album::list(&client, &list, page, None)?;
 //! # assert_eq!(last_results.len(), 10);
 //! #
 //! # let exact = SearchPage::new().with_size(50);
-//! # let exact_results = Album::list(&client, list, exact, 0)?;
+//! # let exact_results = Album::list(&client, &list, exact, None)?;
 //! # assert_eq!(exact_results.len(), 50);
 //! #
 //! let all = search::ALL;
-//! let all_results = Album::list(&client, list, all, 0)?;
+//! let all_results = Album::list(&client, &list, all, None)?;
 //! assert_eq!(all_results.len(), 50);
 //! #
 //! # Ok(())
 //! # }
 //! # fn main() { }
 //! ```
+//!
+//! Rather than paging by hand, [`Pages`] walks any paged endpoint until it is
+//! exhausted, fetching each page as it is needed. Some endpoints provide one
+//! directly.
+//!
+//! ```no_run
+//! # extern crate sunk;
+//! # use sunk::{Album, Client, ListType};
+//! #
+//! # fn run() -> sunk::Result<()> {
+//! # let site = "https://demo.subsonic.org";
+//! # let username = "guest3";
+//! # let password = "guest";
+//! # let client = Client::new(site, username, password)?;
+//! for album in Album::iter(&client, ListType::AlphaByName) {
+//!     println!("{}", album?.name);
+//! }
+//! # Ok(())
+//! # }
+//! # fn main() { }
+//! ```
+//!
+//! [`Pages`]: ./struct.Pages.html
 
 use song::Song;
+use std::collections::VecDeque;
 use std::fmt;
-use {Album, Artist};
+use {Album, Artist, Client, Result};
 
 /// The maximum number of results most searches will accept.
 pub const ALL: SearchPage = SearchPage {
@@ -193,7 +217,7 @@ pub const NONE: SearchPage = SearchPage {
 pub struct SearchPage {
     /// The number of results to return.
     pub count: usize,
-    /// The page offset.
+    /// The number of results to skip.
     pub offset: usize,
 }
 
@@ -206,9 +230,12 @@ impl SearchPage {
         }
     }
 
-    /// Creates the configuration at the provided page.
-    pub fn at_page(offset: usize) -> SearchPage {
-        SearchPage { offset, count: 20 }
+    /// Creates the configuration at the provided page, counting from zero.
+    pub fn at_page(page: usize) -> SearchPage {
+        SearchPage {
+            offset: page * 20,
+            count: 20,
+        }
     }
 
     /// Sets the configuration to the given size.
@@ -219,14 +246,14 @@ impl SearchPage {
         }
     }
 
-    /// Advances the page.
+    /// Advances the page, skipping the results of the current one.
     pub fn next(&mut self) {
-        self.offset += 1;
+        self.offset += self.count;
     }
 
-    /// Decrements the page.
+    /// Moves back a page, stopping at the first one.
     pub fn prev(&mut self) {
-        self.offset -= 1;
+        self.offset = self.offset.saturating_sub(self.count);
     }
 }
 
@@ -238,12 +265,10 @@ impl Default for SearchPage {
 
 impl fmt::Display for SearchPage {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "search range {}-{}",
-            self.count * self.offset,
-            (self.count + 1) * self.offset - 1
-        )
+        match self.count {
+            0 => write!(f, "empty search range"),
+            n => write!(f, "search range {}-{}", self.offset, self.offset + n - 1),
+        }
     }
 }
 
@@ -263,3 +288,255 @@ pub struct SearchResult {
     #[serde(default)]
     pub songs: Vec<Song>,
 }
+
+type Fetch<'a, T> = Box<dyn FnMut(SearchPage) -> Result<Vec<T>> + 'a>;
+
+/// An iterator over every result of a paged endpoint.
+///
+/// Pages are fetched as they are needed, starting from the given page and
+/// moving on by its size. The iterator stops once a page comes back with
+/// fewer results than were asked for. If fetching a page fails, the error is
+/// yielded and the iterator stops.
+///
+/// Servers cap the number of results a single request returns, usually at
+/// 500; asking for a larger page will stop the iterator after the first one.
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate sunk;
+/// use sunk::search::{Pages, SearchPage};
+/// use sunk::song::Song;
+/// use sunk::Client;
+///
+/// # fn run() -> sunk::Result<()> {
+/// # let site = "http://demo.subsonic.org";
+/// # let user = "guest3";
+/// # let password = "guest";
+/// let client = Client::new(site, user, password)?;
+/// let pages = Pages::new(SearchPage::new(), |page| {
+///     Song::list_in_genre(&client, "Electronic", page, None)
+/// });
+///
+/// for song in pages {
+///     println!("{}", song?.title);
+/// }
+/// # Ok(())
+/// # }
+/// # fn main() { }
+/// ```
+pub struct Pages<'a, T> {
+    fetch: Fetch<'a, T>,
+    page: Option<SearchPage>,
+    buffer: VecDeque<T>,
+}
+
+impl<'a, T> Pages<'a, T> {
+    /// Creates an iterator that calls `fetch` for each page, starting at
+    /// `page`.
+    pub fn new<F>(page: SearchPage, fetch: F) -> Pages<'a, T>
+    where
+        F: FnMut(SearchPage) -> Result<Vec<T>> + 'a,
+    {
+        Pages {
+            fetch: Box::new(fetch),
+            page: if page.count == 0 { None } else { Some(page) },
+            buffer: VecDeque::new(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Pages<'a, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        loop {
+            if let Some(item) = self.buffer.pop_front() {
+                return Some(Ok(item));
+            }
+
+            let page = self.page?;
+            match (self.fetch)(page) {
+                Ok(items) => {
+                    self.page = advance(page, items.len());
+                    self.buffer.extend(items);
+                }
+                Err(e) => {
+                    self.page = None;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+impl<'a, T> fmt::Debug for Pages<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Pages")
+            .field("page", &self.page)
+            .field("buffered", &self.buffer.len())
+            .finish()
+    }
+}
+
+/// An iterator over the pages of a search.
+///
+/// Artists, albums and songs are paged separately, each moving on by the size
+/// of its own page. Each item holds the next page of every kind of result that
+/// is not yet exhausted; the iterator stops once all of them are. A page with
+/// a size of zero, such as [`NONE`], skips that kind of result entirely.
+///
+/// Created by [`Client::search_pages`].
+///
+/// [`NONE`]: ./constant.NONE.html
+/// [`Client::search_pages`]: ../struct.Client.html#method.search_pages
+#[derive(Debug)]
+pub struct SearchPages<'a> {
+    client: &'a Client,
+    query: String,
+    artists: Option<SearchPage>,
+    albums: Option<SearchPage>,
+    songs: Option<SearchPage>,
+}
+
+impl<'a> SearchPages<'a> {
+    pub(crate) fn new(
+        client: &'a Client,
+        query: &str,
+        artist_page: SearchPage,
+        album_page: SearchPage,
+        song_page: SearchPage,
+    ) -> SearchPages<'a> {
+        let cursor = |page: SearchPage| if page.count == 0 { None } else { Some(page) };
+        SearchPages {
+            client,
+            query: query.to_string(),
+            artists: cursor(artist_page),
+            albums: cursor(album_page),
+            songs: cursor(song_page),
+        }
+    }
+}
+
+impl<'a> Iterator for SearchPages<'a> {
+    type Item = Result<SearchResult>;
+
+    fn next(&mut self) -> Option<Result<SearchResult>> {
+        if self.artists.is_none() && self.albums.is_none() && self.songs.is_none() {
+            return None;
+        }
+
+        let res = self.client.search(
+            &self.query,
+            self.artists.unwrap_or(NONE),
+            self.albums.unwrap_or(NONE),
+            self.songs.unwrap_or(NONE),
+        );
+
+        match res {
+            Ok(res) => {
+                self.artists = self.artists.and_then(|p| advance(p, res.artists.len()));
+                self.albums = self.albums.and_then(|p| advance(p, res.albums.len()));
+                self.songs = self.songs.and_then(|p| advance(p, res.songs.len()));
+                Some(Ok(res))
+            }
+            Err(e) => {
+                self.artists = None;
+                self.albums = None;
+                self.songs = None;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Returns the page after `page`, or `None` if `found` shows it was the last.
+fn advance(mut page: SearchPage, found: usize) -> Option<SearchPage> {
+    if found < page.count {
+        None
+    } else {
+        page.next();
+        Some(page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util;
+    use Error;
+
+    #[test]
+    fn next_skips_a_full_page() {
+        let mut page = SearchPage::new().with_size(10);
+        page.next();
+        page.next();
+        assert_eq!(page.offset, 20);
+        page.prev();
+        assert_eq!(page.offset, 10);
+        assert_eq!(page.to_string(), "search range 10-19");
+
+        assert_eq!(SearchPage::at_page(2).offset, 40);
+    }
+
+    #[test]
+    fn pages_until_exhausted() {
+        let mut offsets = Vec::new();
+        let items = Pages::new(SearchPage::new().with_size(2), |page| {
+            offsets.push(page.offset);
+            Ok((page.offset..5).take(page.count).collect())
+        })
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+
+        assert_eq!(items, vec![0, 1, 2, 3, 4]);
+        assert_eq!(offsets, vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn pages_stop_on_error() {
+        let mut calls = 0;
+        {
+            let mut pages = Pages::new(SearchPage::new(), |_| -> Result<Vec<u8>> {
+                calls += 1;
+                Err(Error::Other("no"))
+            });
+            assert!(pages.next().unwrap().is_err());
+            assert!(pages.next().is_none());
+        }
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn search_pages_track_each_cursor() {
+        let artist = r#"{ "id": "1", "name": "Misteur Valaire", "albumCount": 1 }"#;
+        let album = r#"{
+            "id": "1", "name": "Bellevue", "artist": "Misteur Valaire",
+            "artistId": "1", "songCount": 9, "duration": 1920,
+            "created": "2017-03-12T11:07:25.000Z"
+        }"#;
+        let (site, heads) = test_util::serve(vec![
+            test_util::ok_response(&format!(
+                r#""searchResult3": {{ "artist": [{0}], "album": [{1}, {1}] }}"#,
+                artist, album
+            )),
+            test_util::ok_response(&format!(r#""searchResult3": {{ "album": [{}] }}"#, album)),
+        ]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+        let page = SearchPage::new().with_size(2);
+
+        let results = cli
+            .search_pages("bellevue", page, page, NONE)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].albums.len(), 2);
+        assert_eq!(results[1].albums.len(), 1);
+
+        let first = heads.recv().unwrap();
+        assert!(first.contains("&artistCount=2&artistOffset=0&albumCount=2&albumOffset=0"));
+        assert!(first.contains("&songCount=0&songOffset=0 "));
+        let second = heads.recv().unwrap();
+        assert!(second.contains("&artistCount=0&artistOffset=0&albumCount=2&albumOffset=2"));
+    }
+}
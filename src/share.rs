@@ -0,0 +1,392 @@
+use serde::de::{self, Deserialize, Deserializer};
+use serde_json;
+use std::result;
+
+#[cfg(feature = "async")]
+use futures::Future;
+use id::{DirectoryId, MediaId, ShareId};
+use query::Query;
+use video::Video;
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture};
+use {Client, Error, Result, Song};
+
+/// A public link to media on the server.
+///
+/// Shares allow media to be played by anyone who has the link, without
+/// needing an account on the server. A share may hold any mix of songs,
+/// videos, and directories such as albums.
+#[derive(Debug, Clone)]
+pub struct Share {
+    /// The ID of the share.
+    pub id: ShareId,
+    /// The public URL of the share.
+    pub url: String,
+    /// The description of the share, if any.
+    pub description: Option<String>,
+    /// The user who created the share.
+    pub user: String,
+    /// When the share was created.
+    pub created: String,
+    /// When the share expires, if ever.
+    pub expires: Option<String>,
+    /// When the share was last visited, if ever.
+    pub last_visited: Option<String>,
+    /// The number of times the share has been visited.
+    pub visit_count: u64,
+    /// The media in the share.
+    pub entries: Vec<ShareEntry>,
+}
+
+/// A piece of media contained in a [`Share`].
+///
+/// [`Share`]: ./struct.Share.html
+#[derive(Debug, Clone)]
+pub enum ShareEntry {
+    /// A shared song.
+    Song(Song),
+    /// A shared video.
+    Video(Video),
+    /// A shared directory, such as an album.
+    Directory {
+        /// The ID of the directory.
+        id: DirectoryId,
+        /// The name of the directory.
+        title: String,
+    },
+}
+
+impl Share {
+    /// Lists the shares the user is allowed to manage.
+    pub fn list(client: &Client) -> Result<Vec<Share>> {
+        let share = client.get("getShares", Query::none())?;
+        Ok(get_list_as!(share, Share))
+    }
+
+    /// Creates a share of the media with the given IDs.
+    ///
+    /// The IDs may be of any mix of songs, videos, and albums. If provided,
+    /// `expires` is the time the share expires, in milliseconds since the
+    /// Unix epoch.
+    ///
+    /// # Errors
+    ///
+    /// Aside from errors the `Client` may cause, the method will error if the
+    /// user is not allowed to share media.
+    pub fn create<'a, S, U>(
+        client: &Client,
+        ids: &[MediaId],
+        description: S,
+        expires: U,
+    ) -> Result<Share>
+    where
+        S: Into<Option<&'a str>>,
+        U: Into<Option<u64>>,
+    {
+        let args = create_query(ids, description.into(), expires.into());
+        let share = client.get("createShare", args)?;
+        first_share(share)
+    }
+
+    /// Updates the description and expiry time of the share.
+    ///
+    /// Arguments that are `None` are left unchanged. `expires` is in
+    /// milliseconds since the Unix epoch.
+    pub fn update<'a, S, U>(&self, client: &Client, description: S, expires: U) -> Result<()>
+    where
+        S: Into<Option<&'a str>>,
+        U: Into<Option<u64>>,
+    {
+        client.get(
+            "updateShare",
+            update_query(&self.id, description.into(), expires.into()),
+        )?;
+        Ok(())
+    }
+
+    /// Removes the share from the server.
+    pub fn delete(&self, client: &Client) -> Result<()> {
+        client.get("deleteShare", Query::with("id", &self.id))?;
+        Ok(())
+    }
+
+    /// Returns the songs in the share.
+    pub fn songs(&self) -> Vec<&Song> {
+        self.entries
+            .iter()
+            .filter_map(|e| match *e {
+                ShareEntry::Song(ref s) => Some(s),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the videos in the share.
+    pub fn videos(&self) -> Vec<&Video> {
+        self.entries
+            .iter()
+            .filter_map(|e| match *e {
+                ShareEntry::Video(ref v) => Some(v),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "async")]
+impl Share {
+    /// Lists the shares the user is allowed to manage without blocking.
+    pub fn list_async(client: &AsyncClient) -> SunkFuture<Vec<Share>> {
+        Box::new(
+            client
+                .get("getShares", Query::none())
+                .and_then(|share| -> Result<_> { Ok(get_list_as!(share, Share)) }),
+        )
+    }
+
+    /// Creates a share of the media with the given IDs without blocking.
+    ///
+    /// See [`create`](#method.create) for details.
+    pub fn create_async<'a, S, U>(
+        client: &AsyncClient,
+        ids: &[MediaId],
+        description: S,
+        expires: U,
+    ) -> SunkFuture<Share>
+    where
+        S: Into<Option<&'a str>>,
+        U: Into<Option<u64>>,
+    {
+        let args = create_query(ids, description.into(), expires.into());
+        Box::new(client.get("createShare", args).and_then(first_share))
+    }
+
+    /// Updates the description and expiry time of the share without blocking.
+    ///
+    /// See [`update`](#method.update) for details.
+    pub fn update_async<'a, S, U>(
+        &self,
+        client: &AsyncClient,
+        description: S,
+        expires: U,
+    ) -> SunkFuture<()>
+    where
+        S: Into<Option<&'a str>>,
+        U: Into<Option<u64>>,
+    {
+        let args = update_query(&self.id, description.into(), expires.into());
+        Box::new(client.get("updateShare", args).map(|_| ()))
+    }
+
+    /// Removes the share from the server without blocking.
+    pub fn delete_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get("deleteShare", Query::with("id", &self.id))
+                .map(|_| ()),
+        )
+    }
+}
+
+fn create_query(ids: &[MediaId], description: Option<&str>, expires: Option<u64>) -> Query {
+    Query::new()
+        .arg_list("id", ids)
+        .arg("description", description)
+        .arg("expires", expires)
+        .build()
+}
+
+fn update_query(id: &ShareId, description: Option<&str>, expires: Option<u64>) -> Query {
+    Query::with("id", id)
+        .arg("description", description)
+        .arg("expires", expires)
+        .build()
+}
+
+/// Extracts the newly created share from a `createShare` response.
+fn first_share(share: serde_json::Value) -> Result<Share> {
+    get_list_as!(share, Share)
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::Other("no share was created"))
+}
+
+impl<'de> Deserialize<'de> for Share {
+    fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct _Share {
+            id: ShareId,
+            url: String,
+            description: Option<String>,
+            username: String,
+            created: String,
+            expires: Option<String>,
+            last_visited: Option<String>,
+            #[serde(default)]
+            visit_count: u64,
+            #[serde(default)]
+            entry: Vec<ShareEntry>,
+        }
+
+        let raw = _Share::deserialize(de)?;
+
+        Ok(Share {
+            id: raw.id,
+            url: raw.url,
+            description: raw.description,
+            user: raw.username,
+            created: raw.created,
+            expires: raw.expires,
+            last_visited: raw.last_visited,
+            visit_count: raw.visit_count,
+            entries: raw.entry,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for ShareEntry {
+    fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct _Kind {
+            id: DirectoryId,
+            #[serde(default)]
+            title: String,
+            #[serde(default)]
+            is_dir: bool,
+            #[serde(default)]
+            is_video: bool,
+        }
+
+        let raw = serde_json::Value::deserialize(de)?;
+        let kind = _Kind::deserialize(&raw).map_err(de::Error::custom)?;
+
+        if kind.is_dir {
+            Ok(ShareEntry::Directory {
+                id: kind.id,
+                title: kind.title,
+            })
+        } else if kind.is_video {
+            Ok(ShareEntry::Video(
+                Video::deserialize(raw).map_err(de::Error::custom)?,
+            ))
+        } else {
+            Ok(ShareEntry::Song(
+                Song::deserialize(raw).map_err(de::Error::custom)?,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util;
+
+    #[test]
+    fn parse_share() {
+        let parsed = serde_json::from_value::<Share>(raw()).unwrap();
+        assert_eq!(parsed.id, "12");
+        assert_eq!(parsed.url, "http://demo.subsonic.org/share/BG6Pz");
+        assert_eq!(parsed.description, Some("Saturday night".to_string()));
+        assert_eq!(parsed.visit_count, 3);
+        assert_eq!(parsed.entries.len(), 2);
+        assert_eq!(parsed.songs()[0].id, "27");
+        match parsed.entries[1] {
+            ShareEntry::Directory { ref id, ref title } => {
+                assert_eq!(id, "25");
+                assert_eq!(title, "Bellevue");
+            }
+            ref e => panic!("expected a directory, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn create_update_and_delete_share() {
+        let body = format!(r#""shares": {{ "share": [{}] }}"#, raw());
+        let (site, heads) = test_util::serve(vec![
+            test_util::ok_response(&body),
+            test_util::ok_response(""),
+            test_util::ok_response(""),
+        ]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+
+        let share = Share::create(
+            &cli,
+            &[MediaId::from(27), MediaId::from(25)],
+            "Saturday night",
+            None,
+        )
+        .unwrap();
+        let head = heads.recv().unwrap();
+        assert!(head.contains("/rest/createShare?"));
+        assert!(head.contains("&id=27&id=25&description=Saturday+night "));
+        assert_eq!(share.id, "12");
+
+        share.update(&cli, None, 1_600_000_000_000).unwrap();
+        assert!(heads.recv().unwrap().contains("/rest/updateShare?"));
+
+        share.delete(&cli).unwrap();
+        assert!(heads.recv().unwrap().contains("/rest/deleteShare?"));
+    }
+
+    #[test]
+    fn share_update_query() {
+        assert_eq!(
+            update_query(&ShareId::from(12), None, Some(1_600_000_000_000)).to_string(),
+            "id=12&expires=1600000000000"
+        );
+    }
+
+    fn raw() -> serde_json::Value {
+        serde_json::from_str(
+            r#"{
+            "id" : "12",
+            "url" : "http://demo.subsonic.org/share/BG6Pz",
+            "description" : "Saturday night",
+            "username" : "guest3",
+            "created" : "2018-02-03T06:22:27.411Z",
+            "expires" : "2018-03-03T06:22:27.411Z",
+            "lastVisited" : "2018-02-04T10:12:01.002Z",
+            "visitCount" : 3,
+            "entry" : [ {
+                "id" : "27",
+                "parent" : "25",
+                "isDir" : false,
+                "title" : "Bellevue Avenue",
+                "album" : "Bellevue",
+                "artist" : "Misteur Valaire",
+                "track" : 1,
+                "coverArt" : "25",
+                "size" : 5400185,
+                "contentType" : "audio/mpeg",
+                "suffix" : "mp3",
+                "duration" : 198,
+                "bitRate" : 216,
+                "path" : "Misteur Valaire/Bellevue/01 - Misteur Valaire - Bellevue Avenue.mp3",
+                "playCount" : 706,
+                "created" : "2017-03-12T11:07:27.000Z",
+                "albumId" : "1",
+                "artistId" : "1",
+                "type" : "music"
+            }, {
+                "id" : "25",
+                "parent" : "24",
+                "isDir" : true,
+                "title" : "Bellevue",
+                "album" : "Bellevue",
+                "artist" : "Misteur Valaire",
+                "coverArt" : "25"
+            } ]
+        }"#,
+        )
+        .unwrap()
+    }
+}
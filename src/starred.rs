@@ -0,0 +1,218 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+#[cfg(feature = "async")]
+use futures::Future;
+use id::MusicFolderId;
+use query::Query;
+use serde_json;
+use {Album, Artist, Client, Result, Song};
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture};
+
+/// The artists, albums, and songs the user has starred.
+///
+/// Unlike [`Client::starred`], these are found through ID3 tags, so their IDs
+/// match those of [`Artist::get`] and [`Album::get`]. Each carries the time it
+/// was starred in its `starred` field.
+///
+/// [`Client::starred`]: ./struct.Client.html#method.starred
+/// [`Artist::get`]: ./struct.Artist.html#method.get
+/// [`Album::get`]: ./struct.Album.html#method.get
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Starred {
+    /// Starred artists.
+    #[serde(rename = "artist")]
+    #[serde(default)]
+    pub artists: Vec<Artist>,
+    /// Starred albums.
+    #[serde(rename = "album")]
+    #[serde(default)]
+    pub albums: Vec<Album>,
+    /// Starred songs.
+    #[serde(rename = "song")]
+    #[serde(default)]
+    pub songs: Vec<Song>,
+}
+
+/// The difference between two snapshots of the user's starred items.
+///
+/// Created by [`Starred::changes_since`].
+///
+/// [`Starred::changes_since`]: ./struct.Starred.html#method.changes_since
+#[derive(Debug, Clone, Default)]
+pub struct StarredChanges {
+    /// Items that have been starred since the earlier snapshot.
+    pub starred: Starred,
+    /// Items that have been unstarred since the earlier snapshot.
+    pub unstarred: Starred,
+}
+
+impl Starred {
+    /// Returns everything the user has starred.
+    ///
+    /// Optionally takes the ID of a music folder to only include the items in
+    /// that folder.
+    pub fn get<U>(client: &Client, folder_id: U) -> Result<Starred>
+    where
+        U: Into<Option<MusicFolderId>>,
+    {
+        let res = client.get(
+            "getStarred2",
+            Query::with("musicFolderId", folder_id.into()),
+        )?;
+        Ok(serde_json::from_value::<Starred>(res)?)
+    }
+
+    /// Returns `true` if nothing is starred.
+    pub fn is_empty(&self) -> bool {
+        self.artists.is_empty() && self.albums.is_empty() && self.songs.is_empty()
+    }
+
+    /// Compares the items against an earlier snapshot.
+    ///
+    /// Items are matched by ID. An item unstarred and starred again between
+    /// the two snapshots is not reported as a change.
+    pub fn changes_since(&self, previous: &Starred) -> StarredChanges {
+        StarredChanges {
+            starred: missing_from(self, previous),
+            unstarred: missing_from(previous, self),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl Starred {
+    /// Returns everything the user has starred without blocking.
+    ///
+    /// The asynchronous counterpart to [`get`](#method.get).
+    pub fn get_async<U>(client: &AsyncClient, folder_id: U) -> SunkFuture<Starred>
+    where
+        U: Into<Option<MusicFolderId>>,
+    {
+        let args = Query::with("musicFolderId", folder_id.into());
+        Box::new(
+            client
+                .get("getStarred2", args)
+                .and_then(|res| Ok(serde_json::from_value::<Starred>(res)?)),
+        )
+    }
+}
+
+impl StarredChanges {
+    /// Returns `true` if nothing was starred or unstarred.
+    pub fn is_empty(&self) -> bool {
+        self.starred.is_empty() && self.unstarred.is_empty()
+    }
+}
+
+/// Returns the items of `from` that are not in `other`.
+fn missing_from(from: &Starred, other: &Starred) -> Starred {
+    Starred {
+        artists: missing(&from.artists, &other.artists, |a| a.id.clone()),
+        albums: missing(&from.albums, &other.albums, |a| a.id.clone()),
+        songs: missing(&from.songs, &other.songs, |s| s.id.clone()),
+    }
+}
+
+fn missing<T, K, F>(from: &[T], other: &[T], id: F) -> Vec<T>
+where
+    T: Clone,
+    K: Eq + Hash,
+    F: Fn(&T) -> K,
+{
+    let ids = other.iter().map(&id).collect::<HashSet<_>>();
+    from.iter()
+        .filter(|t| !ids.contains(&id(t)))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use id::ArtistId;
+    use test_util;
+
+    #[test]
+    fn get_starred() {
+        let body = format!(r#""starred2": {}"#, raw());
+        let (site, heads) = test_util::serve(vec![test_util::ok_response(&body)]);
+        let cli = Client::new(&site, "user", "pass").unwrap();
+
+        let starred = Starred::get(&cli, MusicFolderId::new("2")).unwrap();
+        let head = heads.recv().unwrap();
+        assert!(head.contains("/rest/getStarred2?"));
+        assert!(head.contains("&musicFolderId=2 "));
+
+        assert_eq!(starred.artists[0].id, "1");
+        assert_eq!(
+            starred.artists[0].starred,
+            Some("2018-02-01T10:00:00.000Z".into())
+        );
+        assert_eq!(starred.albums[0].name, "Bellevue");
+        assert!(starred.albums[0].starred.is_some());
+        assert_eq!(starred.songs[0].id, "27");
+        assert!(starred.songs[0].starred.is_some());
+    }
+
+    #[test]
+    fn changes_since_snapshot() {
+        let before = serde_json::from_value::<Starred>(raw()).unwrap();
+        let mut after = before.clone();
+        after.songs.clear();
+        after.artists[0].id = ArtistId::from(5);
+
+        let changes = after.changes_since(&before);
+        assert_eq!(changes.starred.artists[0].id, "5");
+        assert!(changes.starred.albums.is_empty());
+        assert!(changes.starred.songs.is_empty());
+        assert_eq!(changes.unstarred.artists[0].id, "1");
+        assert_eq!(changes.unstarred.songs[0].id, "27");
+        assert!(changes.unstarred.albums.is_empty());
+
+        assert!(after.changes_since(&after).is_empty());
+    }
+
+    fn raw() -> serde_json::Value {
+        serde_json::from_str(
+            r#"{
+            "artist" : [ {
+                "id" : "1",
+                "name" : "Misteur Valaire",
+                "albumCount" : 1,
+                "starred" : "2018-02-01T10:00:00.000Z"
+            } ],
+            "album" : [ {
+                "id" : "1",
+                "name" : "Bellevue",
+                "artist" : "Misteur Valaire",
+                "artistId" : "1",
+                "songCount" : 9,
+                "duration" : 1920,
+                "created" : "2017-03-12T11:07:25.000Z",
+                "starred" : "2018-02-01T10:01:00.000Z"
+            } ],
+            "song" : [ {
+                "id" : "27",
+                "parent" : "25",
+                "isDir" : false,
+                "title" : "Bellevue Avenue",
+                "album" : "Bellevue",
+                "artist" : "Misteur Valaire",
+                "size" : 5400185,
+                "contentType" : "audio/mpeg",
+                "suffix" : "mp3",
+                "duration" : 198,
+                "path" : "Misteur Valaire/Bellevue/01 - Misteur Valaire - Bellevue Avenue.mp3",
+                "created" : "2017-03-12T11:07:27.000Z",
+                "starred" : "2018-02-01T10:02:00.000Z",
+                "albumId" : "1",
+                "artistId" : "1",
+                "type" : "music"
+            } ]
+        }"#,
+        )
+        .unwrap()
+    }
+}
@@ -1,3 +1,8 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
 use client;
 use error;
 
@@ -7,3 +12,68 @@ pub fn demo_site() -> error::Result<client::Client> {
     let password = "guest";
     client::Client::new(site, user, password)
 }
+
+/// Serves each raw HTTP response, in order, to a new connection on a local
+/// port. Returns the address of the server and a receiver for the head of
+/// every request it receives.
+pub fn serve(responses: Vec<Vec<u8>>) -> (String, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut head = String::new();
+            {
+                let mut reader = BufReader::new(&stream);
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                        break;
+                    }
+                    head.push_str(&line);
+                }
+            }
+            let _ = tx.send(head);
+            stream.write_all(&response).unwrap();
+        }
+    });
+
+    (addr, rx)
+}
+
+/// Builds a raw HTTP response with the given status line, extra headers and
+/// body.
+pub fn http_response(status: &str, headers: &[&str], body: &[u8]) -> Vec<u8> {
+    let mut res = format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        body.len()
+    );
+    for header in headers {
+        res.push_str(header);
+        res.push_str("\r\n");
+    }
+    res.push_str("\r\n");
+
+    let mut res = res.into_bytes();
+    res.extend_from_slice(body);
+    res
+}
+
+/// Builds a successful API response carrying the given JSON members, such as
+/// `"playlist": { ... }`.
+pub fn ok_response(members: &str) -> Vec<u8> {
+    let mut body = String::from(r#"{"subsonic-response":{"status":"ok","version":"1.16.1""#);
+    if !members.is_empty() {
+        body.push(',');
+        body.push_str(members);
+    }
+    body.push_str("}}");
+    http_response(
+        "200 OK",
+        &["Content-Type: application/json"],
+        body.as_bytes(),
+    )
+}
@@ -1,6 +1,11 @@
 use serde_json;
 
+#[cfg(feature = "async")]
+use futures::Future;
+use id::MusicFolderId;
 use query::Query;
+#[cfg(feature = "async")]
+use {AsyncClient, SunkFuture};
 use {Client, Result};
 
 /// A struct representing a Subsonic user.
@@ -67,7 +72,7 @@ pub struct User {
     pub avatar_last_changed: String,
     /// The list of media folders the user has access to.
     #[serde(rename = "folder")]
-    pub folders: Vec<u64>,
+    pub folders: Vec<MusicFolderId>,
     #[serde(default)]
     _private: bool,
 }
@@ -157,8 +162,13 @@ impl User {
     /// # }
     /// ```
     pub fn update(&self, client: &Client) -> Result<()> {
-        let args = Query::with("username", self.username.as_ref())
-            .arg("email", self.email.as_ref())
+        client.get("updateUser", self.update_query())?;
+        Ok(())
+    }
+
+    fn update_query(&self) -> Query {
+        Query::with("username", self.username.as_str())
+            .arg("email", self.email.as_str())
             .arg("ldapAuthenticated", self.ldap_authenticated)
             .arg("adminRole", self.admin_role)
             .arg("settingsRole", self.settings_role)
@@ -173,9 +183,61 @@ impl User {
             .arg("videoConversionRole", self.video_conversion_role)
             .arg_list("musicFolderId", &self.folders.clone())
             .arg("maxBitRate", self.max_bit_rate)
+            .build()
+    }
+}
+
+#[cfg(feature = "async")]
+impl User {
+    /// Fetches a single user's information from the server without blocking.
+    pub fn get_async(client: &AsyncClient, username: &str) -> SunkFuture<User> {
+        Box::new(
+            client
+                .get("getUser", Query::with("username", username))
+                .and_then(|res| Ok(serde_json::from_value::<User>(res)?)),
+        )
+    }
+
+    /// Lists all users on the server without blocking.
+    ///
+    /// See [`list`](#method.list) for the permissions required.
+    pub fn list_async(client: &AsyncClient) -> SunkFuture<Vec<User>> {
+        Box::new(
+            client
+                .get("getUsers", Query::none())
+                .and_then(|user| -> Result<_> { Ok(get_list_as!(user, User)) }),
+        )
+    }
+
+    /// Changes the user's password without blocking.
+    pub fn change_password_async(&self, client: &AsyncClient, password: &str) -> SunkFuture<()> {
+        let args = Query::with("username", self.username.as_str())
+            .arg("password", password)
             .build();
-        client.get("updateUser", args)?;
-        Ok(())
+        Box::new(client.get("changePassword", args).map(|_| ()))
+    }
+
+    /// Returns the user's avatar image as a collection of bytes without
+    /// blocking.
+    pub fn avatar_async(&self, client: &AsyncClient) -> SunkFuture<Vec<u8>> {
+        client.get_bytes("getAvatar", Query::with("username", self.username.as_str()))
+    }
+
+    /// Removes the user from the Subsonic server without blocking.
+    pub fn delete_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(
+            client
+                .get(
+                    "deleteUser",
+                    Query::with("username", self.username.as_str()),
+                )
+                .map(|_| ()),
+        )
+    }
+
+    /// Pushes any changes made to the user to the server without blocking.
+    pub fn update_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(client.get("updateUser", self.update_query()).map(|_| ()))
     }
 }
 
@@ -197,7 +259,7 @@ pub struct UserBuilder {
     podcast_role: bool,
     share_role: bool,
     video_conversion_role: bool,
-    folders: Vec<u64>,
+    folders: Vec<MusicFolderId>,
     max_bit_rate: u64,
 }
 
@@ -252,16 +314,27 @@ impl UserBuilder {
     /// Allows the user to start video coversions.
     build!(video_conversion_role: bool);
     /// IDs of the music folders the user is allowed to access.
-    build!(folders: &[u64]);
+    build!(folders: &[MusicFolderId]);
     /// The maximum bit rate (in Kbps) the user is allowed to stream at. Higher
     /// bit rate streams will be downsampled to their limit.
     build!(max_bit_rate: u64);
 
     /// Pushes a defined new user to the Subsonic server.
     pub fn create(&self, client: &Client) -> Result<()> {
-        let args = Query::with("username", self.username.as_ref())
-            .arg("password", self.password.as_ref())
-            .arg("email", self.email.as_ref())
+        client.get("createUser", self.create_query())?;
+        Ok(())
+    }
+
+    /// Pushes a defined new user to the Subsonic server without blocking.
+    #[cfg(feature = "async")]
+    pub fn create_async(&self, client: &AsyncClient) -> SunkFuture<()> {
+        Box::new(client.get("createUser", self.create_query()).map(|_| ()))
+    }
+
+    fn create_query(&self) -> Query {
+        Query::with("username", self.username.as_str())
+            .arg("password", self.password.as_str())
+            .arg("email", self.email.as_str())
             .arg("ldapAuthenticated", self.ldap_authenticated)
             .arg("adminRole", self.admin_role)
             .arg("settingsRole", self.settings_role)
@@ -276,9 +349,7 @@ impl UserBuilder {
             .arg("videoConversionRole", self.video_conversion_role)
             .arg_list("musicFolderId", &self.folders)
             .arg("maxBitRate", self.max_bit_rate)
-            .build();
-        client.get("createUser", args)?;
-        Ok(())
+            .build()
     }
 }
 
@@ -1,66 +1,234 @@
-use std::{convert, fmt};
-
-#[derive(PartialEq, PartialOrd, Eq, Ord, Copy, Clone)]
-pub struct Version(u8, u8, u8);
-
-impl convert::From<String> for Version {
-    fn from(s: String) -> Version {
-        let mut spl = s.split('.');
-
-        macro_rules! ver {
-            ($v:ident) => {
-                let $v = match spl.next() {
-                    Some(n) => n.parse::<u8>().unwrap(),
-                    None => 0,
-                };
-            };
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::{fmt, result};
+
+use error::VersionError;
+
+/// A version of the Subsonic API.
+///
+/// Versions compare by their major, minor and patch numbers, but keep the
+/// string they were parsed from for display. Servers may report versions with
+/// a suffix or extra components, such as `1.16.1-SNAPSHOT` or `1.16.1.0`;
+/// both parse as `1.16.1`.
+///
+/// # Examples
+///
+/// ```
+/// use sunk::Version;
+///
+/// let snapshot = "1.16.1-SNAPSHOT".parse::<Version>().unwrap();
+/// assert_eq!(snapshot, Version::new(1, 16, 1));
+/// assert!(snapshot > Version::new(1, 12, 0));
+/// assert_eq!(snapshot.to_string(), "1.16.1-SNAPSHOT");
+///
+/// assert!("".parse::<Version>().is_err());
+/// ```
+#[derive(Clone)]
+pub struct Version {
+    major: u16,
+    minor: u16,
+    patch: u16,
+    raw: String,
+}
+
+impl Version {
+    /// Creates a version from its major, minor and patch numbers.
+    pub fn new(major: u16, minor: u16, patch: u16) -> Version {
+        Version {
+            major,
+            minor,
+            patch,
+            raw: format!("{}.{}.{}", major, minor, patch),
+        }
+    }
+
+    /// Returns the major number of the version.
+    pub fn major(&self) -> u16 {
+        self.major
+    }
+
+    /// Returns the minor number of the version.
+    pub fn minor(&self) -> u16 {
+        self.minor
+    }
+
+    /// Returns the patch number of the version.
+    pub fn patch(&self) -> u16 {
+        self.patch
+    }
+
+    /// Returns the string the version was parsed from.
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    fn numbers(&self) -> (u16, u16, u16) {
+        (self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for Version {
+    type Err = VersionError;
+
+    /// Parses a version from its leading numeric components.
+    ///
+    /// Missing components are taken as zero. Parsing stops at the third
+    /// component, or at the first one followed by anything but a dot.
+    fn from_str(s: &str) -> result::Result<Version, VersionError> {
+        let trimmed = s.trim();
+        if trimmed.is_empty() {
+            return Err(VersionError::Empty);
         }
 
-        ver!(major);
-        ver!(minor);
-        ver!(inc);
+        let mut nums = [0u16; 3];
+        let mut rest = trimmed;
+        for (i, num) in nums.iter_mut().enumerate() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            if digits == 0 {
+                if i == 0 {
+                    return Err(VersionError::Invalid(s.to_string()));
+                }
+                break;
+            }
+
+            *num = rest[..digits]
+                .parse()
+                .map_err(|_| VersionError::Invalid(s.to_string()))?;
+            rest = &rest[digits..];
+            if !rest.starts_with('.') {
+                break;
+            }
+            rest = &rest[1..];
+        }
+
+        Ok(Version {
+            major: nums[0],
+            minor: nums[1],
+            patch: nums[2],
+            raw: trimmed.to_string(),
+        })
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Version) -> bool {
+        self.numbers() == other.numbers()
+    }
+}
+
+impl Eq for Version {}
 
-        Version(major, minor, inc)
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Version) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-impl<'a> convert::From<&'a str> for Version {
-    fn from(s: &'a str) -> Version {
-        Version::from(s.to_string())
+impl Ord for Version {
+    fn cmp(&self, other: &Version) -> Ordering {
+        self.numbers().cmp(&other.numbers())
+    }
+}
+
+impl Hash for Version {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.numbers().hash(state)
     }
 }
 
 impl fmt::Debug for Version {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Api: {{ {}.{}.{} }}", self.0, self.1, self.2)
+        write!(f, "Api: {{ {} }}", self.raw)
     }
 }
 
 impl fmt::Display for Version {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+        f.write_str(&self.raw)
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S>(&self, ser: S) -> result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.serialize_str(&self.raw)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D>(de: D) -> result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = String::deserialize(de)?;
+        raw.parse().map_err(de::Error::custom)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::Version;
+    use serde_json;
 
     #[test]
     fn test_parse_api_full() {
-        let s = "1.11.0";
-        let v = Version::from(s);
-        assert_eq!(v.0, 1);
-        assert_eq!(v.1, 11);
-        assert_eq!(v.2, 0);
+        let v = "1.11.0".parse::<Version>().unwrap();
+        assert_eq!(v.major, 1);
+        assert_eq!(v.minor, 11);
+        assert_eq!(v.patch, 0);
     }
 
     #[test]
     fn test_parse_api_no_inc() {
-        let s = "1.12";
-        let v = Version::from(s);
-        assert_eq!(v.0, 1);
-        assert_eq!(v.1, 12);
-        assert_eq!(v.2, 0);
+        let v = "1.12".parse::<Version>().unwrap();
+        assert_eq!(v.major, 1);
+        assert_eq!(v.minor, 12);
+        assert_eq!(v.patch, 0);
+    }
+
+    #[test]
+    fn parse_suffixes_and_extra_components() {
+        for s in &["1.16.1-SNAPSHOT", "1.16.1.0", " 1.16.1 "] {
+            let v = s.parse::<Version>().unwrap();
+            assert_eq!((v.major, v.minor, v.patch), (1, 16, 1), "{}", s);
+        }
+
+        let v = "1.16-beta.3".parse::<Version>().unwrap();
+        assert_eq!((v.major, v.minor, v.patch), (1, 16, 0));
+        assert_eq!(v.to_string(), "1.16-beta.3");
+    }
+
+    #[test]
+    fn parse_invalid_versions() {
+        assert!("".parse::<Version>().is_err());
+        assert!("SNAPSHOT".parse::<Version>().is_err());
+        assert!("1.99999".parse::<Version>().is_err());
+        assert!("unknown".parse::<Version>().is_err());
+    }
+
+    #[test]
+    fn new_version() {
+        let v = Version::new(1, 16, 1);
+        assert_eq!(v, "1.16.1".parse().unwrap());
+        assert_eq!(v.as_str(), "1.16.1");
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let v = "1.16.1-SNAPSHOT".parse::<Version>().unwrap();
+        let json = serde_json::to_string(&v).unwrap();
+        assert_eq!(json, r#""1.16.1-SNAPSHOT""#);
+
+        let back = serde_json::from_str::<Version>(&json).unwrap();
+        assert_eq!(back, v);
+        assert_eq!(back.as_str(), v.as_str());
+        assert!(serde_json::from_str::<Version>(r#""""#).is_err());
     }
 }